@@ -1,6 +0,0 @@
-/* Linker script for the STM32F103C8T6 */
-MEMORY
-{
-  FLASH : ORIGIN = 0x08000000, LENGTH = 128K
-  RAM : ORIGIN = 0x20000000, LENGTH = 20K
-}
\ No newline at end of file
@@ -3,8 +3,12 @@
 use hal;
 use hal::digital::OutputPin;
 
+use super::command::VcomhLevel;
+use super::config::{clamp_multiplex, DisplayConfig};
+use super::displayrotation::DisplayRotation;
 use super::displaysize::DisplaySize;
-use super::interface::{I2cInterface, SpiInterface};
+use super::error::Error;
+use super::interface::{DisplayInterface, I2cInterface, Spi3WireInterface, SpiInterface};
 use super::SSD1306;
 
 /// Communication interface factory
@@ -12,14 +16,27 @@ use super::SSD1306;
 pub struct Builder {
     display_size: DisplaySize,
     i2c_addr: u8,
+    i2c_retries: u8,
+    max_transaction_bytes: usize,
+    config: DisplayConfig,
+}
+
+impl Default for Builder {
+    /// Same as `Builder::new()`
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Builder {
     /// Create new builder for default size of 128 x 64 pixels.
     pub fn new() -> Self {
         Self {
-            display_size: DisplaySize::Display128x64,
+            display_size: DisplaySize::default(),
             i2c_addr: 0x3c,
+            i2c_retries: 0,
+            max_transaction_bytes: usize::MAX,
+            config: DisplayConfig::default(),
         }
     }
 
@@ -31,6 +48,17 @@ impl Builder {
         }
     }
 
+    /// Set the rotation of the display. Defaults to no rotation.
+    pub fn with_rotation(&self, rotation: DisplayRotation) -> Self {
+        Self {
+            config: DisplayConfig {
+                rotation,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
     /// Set the I2C address to use. Defaults to 0x3C which seems to be the most common address.
     /// The other address specified in the datasheet is 0x3D.
     ///
@@ -39,20 +67,265 @@ impl Builder {
         Self { i2c_addr, ..*self }
     }
 
+    /// Set how many times a failed I2C transaction is retried before the error is returned.
+    /// Defaults to 0. Raising this can hide occasional glitches on long or noisy buses.
+    ///
+    /// Ignored when using SPI interface
+    pub fn with_i2c_retries(&self, i2c_retries: u8) -> Self {
+        Self {
+            i2c_retries,
+            ..*self
+        }
+    }
+
+    /// Limit the number of bytes sent in a single I2C or SPI transaction, for HALs that would
+    /// otherwise silently truncate long writes. Longer writes are split into transactions that
+    /// fit. Defaults to no limit. See
+    /// `I2cInterface::with_max_transaction_bytes()`, `SpiInterface::with_max_transaction_bytes()`
+    /// and `Spi3WireInterface::with_max_transaction_bytes()` for how each interface applies it.
+    pub fn with_max_transaction_bytes(&self, max_transaction_bytes: usize) -> Self {
+        Self {
+            max_transaction_bytes,
+            ..*self
+        }
+    }
+
+    /// Override the segment remap picked for the rotation, for modules mounted mirrored. See
+    /// `SSD1306::set_segment_remap()` for the common combinations with `with_reverse_com_dir()`.
+    pub fn with_segment_remap(&self, remap: bool) -> Self {
+        Self {
+            config: DisplayConfig {
+                segment_remap: Some(remap),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Override the COM scan direction picked for the rotation, for modules mounted mirrored. See
+    /// `SSD1306::set_reverse_com_dir()`.
+    pub fn with_reverse_com_dir(&self, reverse: bool) -> Self {
+        Self {
+            config: DisplayConfig {
+                reverse_com_dir: Some(reverse),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Override the COM pin hardware configuration, for modules whose rows are wired differently
+    /// than usual for their size. By default this is picked based on the display size. See
+    /// `SSD1306::set_com_pin_config()`.
+    pub fn with_com_pin_config(&self, alternative: bool, left_right_remap: bool) -> Self {
+        Self {
+            config: DisplayConfig {
+                com_pins: Some((alternative, left_right_remap)),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the display clock divide ratio minus one and the oscillator frequency, both 4 bit
+    /// values. Defaults to 0 and 8. Values are clamped to 15. See
+    /// `SSD1306::set_display_clock()` for how these affect the frame rate.
+    pub fn with_display_clock(&self, divide: u8, osc_freq: u8) -> Self {
+        Self {
+            config: DisplayConfig {
+                clock_divide: divide.min(15),
+                osc_freq: osc_freq.min(15),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the contrast level `init()` brings the display up with. Higher is brighter. Defaults
+    /// to 0x8F.
+    pub fn with_contrast(&self, contrast: u8) -> Self {
+        Self {
+            config: DisplayConfig {
+                contrast,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the pre-charge period phase 1 and phase 2 lengths in display clocks. Defaults to 1 and
+    /// 15. Each value is clamped to the valid range of 1 to 15.
+    pub fn with_precharge(&self, phase1: u8, phase2: u8) -> Self {
+        Self {
+            config: DisplayConfig {
+                precharge: (phase1.clamp(1, 15), phase2.clamp(1, 15)),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the VCOMH deselect level. Defaults to `VcomhLevel::Auto`. A higher level can help
+    /// panels that look washed out.
+    pub fn with_vcomh(&self, vcomh: VcomhLevel) -> Self {
+        Self {
+            config: DisplayConfig {
+                vcomh,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Bring the display up inverted, so that set pixels are dark. Defaults to `false`.
+    pub fn with_invert(&self, inverted: bool) -> Self {
+        Self {
+            config: DisplayConfig {
+                inverted,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the multiplex ratio, i.e. the number of active display rows minus one. Defaults to the
+    /// display height minus one. The value is clamped to the valid range of 15 to 63.
+    pub fn with_multiplex(&self, ratio: u8) -> Self {
+        Self {
+            config: DisplayConfig {
+                multiplex: Some(clamp_multiplex(ratio)),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the vertical display offset in rows. Defaults to 0. The value is clamped to 63.
+    pub fn with_display_offset(&self, offset: u8) -> Self {
+        Self {
+            config: DisplayConfig {
+                display_offset: offset.min(63),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set the display RAM row shown at the top of the display. Defaults to 0. The value is
+    /// clamped to 63. See `SSD1306::set_start_line()`.
+    pub fn with_start_line(&self, line: u8) -> Self {
+        Self {
+            config: DisplayConfig {
+                start_line: line.min(63),
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Enable or disable the internal charge pump. Defaults to `true`. Modules that supply the
+    /// panel voltage externally need it turned off.
+    pub fn with_charge_pump(&self, charge_pump: bool) -> Self {
+        Self {
+            config: DisplayConfig {
+                charge_pump,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Configure for panels powered from an external VCC supply instead of the internal charge
+    /// pump. This turns the charge pump off, sets the pre-charge phases to 2 and 2 (0x22) and the
+    /// contrast to 0x9F, the values recommended for external VCC. Options set after this override
+    /// the preset.
+    pub fn with_external_vcc(&self) -> Self {
+        Self {
+            config: DisplayConfig {
+                charge_pump: false,
+                precharge: (0x2, 0x2),
+                contrast: 0x9F,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
+    /// Set extra command bytes to send during `init()`, right before the display is turned on.
+    /// Use this for clones like the SSD1309 that need an additional quirk command. The bytes are
+    /// sent verbatim as commands.
+    pub fn with_extra_init(&self, extra_init: &'static [u8]) -> Self {
+        Self {
+            config: DisplayConfig {
+                extra_init,
+                ..self.config
+            },
+            ..*self
+        }
+    }
+
     /// Create i2c communication interface
     pub fn connect_i2c<I2C>(&self, i2c: I2C) -> SSD1306<I2cInterface<I2C>>
     where
-        I2C: hal::blocking::i2c::Write,
+        I2C: hal::i2c::I2c,
     {
-        SSD1306::new(I2cInterface::new(i2c, self.i2c_addr), self.display_size)
+        SSD1306::with_config(
+            I2cInterface::new(i2c, self.i2c_addr)
+                .with_retries(self.i2c_retries)
+                .with_max_transaction_bytes(self.max_transaction_bytes),
+            self.display_size,
+            self.config,
+        )
     }
 
     /// Create spi communication interface
     pub fn connect_spi<SPI, DC>(&self, spi: SPI, dc: DC) -> SSD1306<SpiInterface<SPI, DC>>
     where
-        SPI: hal::blocking::spi::Transfer<u8> + hal::blocking::spi::Write<u8>,
+        SPI: hal::spi::SpiDevice,
         DC: OutputPin,
     {
-        SSD1306::new(SpiInterface::new(spi, dc), self.display_size)
+        SSD1306::with_config(
+            SpiInterface::new(spi, dc).with_max_transaction_bytes(self.max_transaction_bytes),
+            self.display_size,
+            self.config,
+        )
+    }
+
+    /// Create 3-wire spi communication interface. The SPI peripheral must be configured for 9 bit
+    /// frames.
+    pub fn connect_spi_3wire<SPI>(&self, spi: SPI) -> SSD1306<Spi3WireInterface<SPI>>
+    where
+        SPI: hal::spi::SpiDevice<u16>,
+    {
+        SSD1306::with_config(
+            Spi3WireInterface::new(spi).with_max_transaction_bytes(self.max_transaction_bytes),
+            self.display_size,
+            self.config,
+        )
+    }
+
+    /// Create a driver on top of an already set up display interface, e.g. a `LoggingInterface`
+    /// wrapping one of the bus interfaces. The I2C address, retry and transaction size settings
+    /// are ignored, set those on the interface itself.
+    pub fn connect<DI>(&self, iface: DI) -> SSD1306<DI>
+    where
+        DI: DisplayInterface,
+    {
+        SSD1306::with_config(iface, self.display_size, self.config)
+    }
+
+    /// Create a driver on top of an already set up display interface that draws into a
+    /// caller-provided framebuffer. See `SSD1306::new_with_buffer()` for the buffer requirements.
+    /// The I2C address, retry and transaction size settings are ignored, set those on the
+    /// interface itself.
+    pub fn connect_with_buffer<'a, DI>(
+        &self,
+        iface: DI,
+        buffer: &'a mut [u8],
+    ) -> Result<SSD1306<DI, &'a mut [u8]>, Error<DI::Error>>
+    where
+        DI: DisplayInterface,
+    {
+        SSD1306::with_buffer_and_config(iface, self.display_size, buffer, self.config)
     }
 }
@@ -1,11 +1,13 @@
+//! SSD1306 Commands
+//!
+//! Only public with the `unstable-commands` feature. The command set may change between versions.
+
 // Shamefully taken from https://github.com/EdgewaterDevelopment/rust-ssd1306
 
 use super::interface::DisplayInterface;
 
-/// SSD1306 Commands
-
 /// Commands
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
 pub enum Command {
     /// Set contrast. Higher number is higher contrast. Default = 0x7F
@@ -20,12 +22,12 @@ pub enum Command {
     /// Set up horizontal scrolling.
     /// Values are scroll direction, start page, end page,
     /// and number of frames per step.
-    HScrollSetup(HScrollDir, Page, Page, NFrames),
+    HScrollSetup(HScrollDir, Page, Page, ScrollInterval),
     /// Set up horizontal + vertical scrolling.
     /// Values are scroll direction, start page, end page,
     /// number of frames per step, and vertical scrolling offset.
     /// Scrolling offset may be from 0-63
-    VHScrollSetup(VHScrollDir, Page, Page, NFrames, u8),
+    VHScrollSetup(VHScrollDir, Page, Page, ScrollInterval, u8),
     /// Enable scrolling
     EnableScroll(bool),
     /// Setup vertical scroll area.
@@ -253,31 +255,43 @@ impl From<u8> for Page {
     }
 }
 
-/// Frame interval
+/// Number of frames between each scroll step
+///
+/// The datasheet encodes the interval in 3 bits, which don't increase with the interval:
+///
+/// | Frames | Encoding |
+/// |--------|----------|
+/// | 2      | `0b111`  |
+/// | 3      | `0b100`  |
+/// | 4      | `0b101`  |
+/// | 5      | `0b000`  |
+/// | 25     | `0b110`  |
+/// | 64     | `0b001`  |
+/// | 128    | `0b010`  |
+/// | 256    | `0b011`  |
 #[derive(Debug, Clone, Copy)]
 #[allow(dead_code)]
-pub enum NFrames {
+pub enum ScrollInterval {
     /// 2 Frames
-    F2 = 0b111,
+    Frames2 = 0b111,
     /// 3 Frames
-    F3 = 0b100,
+    Frames3 = 0b100,
     /// 4 Frames
-    F4 = 0b101,
+    Frames4 = 0b101,
     /// 5 Frames
-    F5 = 0b000,
+    Frames5 = 0b000,
     /// 25 Frames
-    F25 = 0b110,
+    Frames25 = 0b110,
     /// 64 Frames
-    F64 = 0b001,
+    Frames64 = 0b001,
     /// 128 Frames
-    F128 = 0b010,
+    Frames128 = 0b010,
     /// 256 Frames
-    F256 = 0b011,
+    Frames256 = 0b011,
 }
 
 /// Address mode
 #[derive(Debug, Clone, Copy)]
-#[allow(dead_code)]
 pub enum AddrMode {
     /// Horizontal mode
     Horizontal = 0b00,
@@ -292,7 +306,7 @@ pub enum AddrMode {
 #[allow(dead_code)]
 pub enum VcomhLevel {
     /// 0.65 * Vcc
-    V065 = 0b001,
+    V065 = 0b000,
     /// 0.77 * Vcc
     V077 = 0b010,
     /// 0.83 * Vcc
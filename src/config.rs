@@ -0,0 +1,68 @@
+//! Display configuration applied by `init()`
+
+use super::command::VcomhLevel;
+use super::displayrotation::DisplayRotation;
+
+/// Initial display settings, set up through the `Builder` and updated by the runtime setters on
+/// the driver so that `init()` always restores the last setting.
+#[derive(Clone, Copy)]
+pub struct DisplayConfig {
+    /// Display rotation
+    pub rotation: DisplayRotation,
+    /// Segment remap override. `None` picks the remap matching the rotation
+    pub segment_remap: Option<bool>,
+    /// COM scan direction override. `None` picks the direction matching the rotation
+    pub reverse_com_dir: Option<bool>,
+    /// COM pin configuration as (alternative, left/right remap). `None` picks a configuration
+    /// based on the display size
+    pub com_pins: Option<(bool, bool)>,
+    /// Display clock divide ratio minus one
+    pub clock_divide: u8,
+    /// Oscillator frequency setting
+    pub osc_freq: u8,
+    /// Contrast level
+    pub contrast: u8,
+    /// Pre-charge period phase 1 and phase 2 lengths in display clocks
+    pub precharge: (u8, u8),
+    /// VCOMH deselect level
+    pub vcomh: VcomhLevel,
+    /// Whether the display is inverted
+    pub inverted: bool,
+    /// Whether the internal charge pump is used to generate the panel voltage
+    pub charge_pump: bool,
+    /// Multiplex ratio. `None` uses the display height
+    pub multiplex: Option<u8>,
+    /// Vertical shift of the display
+    pub display_offset: u8,
+    /// Display RAM row shown at the top of the display
+    pub start_line: u8,
+    /// Raw command bytes sent at the end of `init()`, just before the display is turned on
+    pub extra_init: &'static [u8],
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        Self {
+            rotation: DisplayRotation::Rotate0,
+            segment_remap: None,
+            reverse_com_dir: None,
+            com_pins: None,
+            clock_divide: 0x0,
+            osc_freq: 0x8,
+            contrast: 0x8F,
+            precharge: (0x1, 0xF),
+            vcomh: VcomhLevel::Auto,
+            inverted: false,
+            charge_pump: true,
+            multiplex: None,
+            display_offset: 0,
+            start_line: 0,
+            extra_init: &[],
+        }
+    }
+}
+
+/// Clamp a multiplex ratio to the range supported by the controller
+pub fn clamp_multiplex(ratio: u8) -> u8 {
+    ratio.clamp(15, 63)
+}
@@ -0,0 +1,15 @@
+//! Display rotation
+
+/// Display rotation
+#[derive(Clone, Copy, Default)]
+pub enum DisplayRotation {
+    /// No rotation, normal display
+    #[default]
+    Rotate0,
+    /// Rotate by 90 degrees clockwise
+    Rotate90,
+    /// Rotate by 180 degrees clockwise
+    Rotate180,
+    /// Rotate 270 degrees clockwise
+    Rotate270,
+}
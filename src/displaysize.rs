@@ -1,5 +1,7 @@
 //! Display size
 
+use super::displayrotation::DisplayRotation;
+
 /// Display size enumeration
 #[derive(Clone, Copy)]
 pub enum DisplaySize {
@@ -9,16 +11,58 @@ pub enum DisplaySize {
     Display128x32,
     /// 96 by 16 pixels
     Display96x16,
+    /// 64 by 32 pixels, e.g. 0.49" modules
+    Display64x32,
+}
+
+impl Default for DisplaySize {
+    /// The most common display size, 128 by 64 pixels
+    fn default() -> Self {
+        DisplaySize::Display128x64
+    }
 }
 
 impl DisplaySize {
     /// Get integral dimensions from DisplaySize
     // TODO: Use whatever vec2 impl I decide to use here
-    pub fn dimensions(&self) -> (u8, u8) {
+    pub const fn dimensions(&self) -> (u8, u8) {
         match *self {
             DisplaySize::Display128x64 => (128, 64),
             DisplaySize::Display128x32 => (128, 32),
             DisplaySize::Display96x16 => (96, 16),
+            DisplaySize::Display64x32 => (64, 32),
+        }
+    }
+
+    /// The first controller column that is wired to the panel. Narrow panels are usually
+    /// connected to the middle of the 128 columns the SSD1306 drives.
+    pub const fn column_offset(&self) -> u8 {
+        match *self {
+            DisplaySize::Display64x32 => 32,
+            _ => 0,
         }
     }
+
+    /// Get the dimensions as seen by drawing code after `rotation` is applied, i.e. with width
+    /// and height swapped for 90 and 270 degree rotations
+    pub const fn dimensions_for(&self, rotation: DisplayRotation) -> (u8, u8) {
+        let (width, height) = self.dimensions();
+
+        match rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (width, height),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (height, width),
+        }
+    }
+
+    /// Display width in pixels. Usable in constant expressions, e.g.
+    /// `const WIDTH: u8 = DisplaySize::Display128x32.width();`
+    pub const fn width(&self) -> u8 {
+        self.dimensions().0
+    }
+
+    /// Display height in pixels. Usable in constant expressions, e.g.
+    /// `const HEIGHT: u8 = DisplaySize::Display128x32.height();`
+    pub const fn height(&self) -> u8 {
+        self.dimensions().1
+    }
 }
@@ -0,0 +1,14 @@
+//! Error type
+
+use core::convert::Infallible;
+
+/// Errors that can occur when talking to the display
+#[derive(Debug)]
+pub enum Error<CommE, PinE = Infallible> {
+    /// Error from the display interface
+    Comm(CommE),
+    /// Error setting the reset pin
+    Pin(PinE),
+    /// An argument was out of range, or a buffer had the wrong size
+    InvalidArgument,
+}
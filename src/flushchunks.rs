@@ -0,0 +1,142 @@
+//! Page by page flushing
+
+use core::convert::Infallible;
+
+use super::command::{AddrMode, Command};
+use super::displaysize::DisplaySize;
+use super::interface::DisplayInterface;
+use super::send_column_address;
+
+/// Most command bytes a chunk carries: stopping a scroll (1), switching to horizontal addressing
+/// (2) and the column and page windows (3 each)
+const MAX_COMMANDS: usize = 9;
+
+/// The commands and framebuffer data for one page of the display, yielded by
+/// `SSD1306::flush_chunks()`. Send the commands first, then the data.
+#[derive(Clone, Copy)]
+pub struct FlushChunk<'a> {
+    commands: [u8; MAX_COMMANDS],
+    commands_len: usize,
+    data: &'a [u8],
+}
+
+impl<'a> FlushChunk<'a> {
+    /// Get the command bytes that set up the display RAM window for this page
+    pub fn commands(&self) -> &[u8] {
+        &self.commands[..self.commands_len]
+    }
+
+    /// Get the framebuffer data for this page
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Send the commands and data through a display interface
+    pub fn send<DI>(&self, iface: &mut DI) -> Result<(), DI::Error>
+    where
+        DI: DisplayInterface,
+    {
+        iface.send_commands(self.commands())?;
+        iface.send_data(self.data)
+    }
+}
+
+/// Collects the command bytes of a chunk
+struct Commands {
+    buf: [u8; MAX_COMMANDS],
+    len: usize,
+}
+
+impl DisplayInterface for Commands {
+    type Error = Infallible;
+
+    fn send_command(&mut self, cmd: u8) -> Result<(), Infallible> {
+        self.buf[self.len] = cmd;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    fn send_data(&mut self, _buf: &[u8]) -> Result<(), Infallible> {
+        // Only commands are collected, the data is referenced by the chunk directly
+        Ok(())
+    }
+}
+
+/// Iterator over the framebuffer one page at a time, returned by `SSD1306::flush_chunks()`. Each
+/// call to `next()` returns the chunk for the next page, so the caller decides when and how to
+/// send it, e.g. yielding to other tasks in between or handing the data to DMA.
+#[derive(Clone, Copy)]
+pub struct FlushChunks<'a> {
+    buffer: &'a [u8],
+    display_size: DisplaySize,
+    stop_scroll: bool,
+    set_horizontal: bool,
+    page: u8,
+}
+
+impl<'a> FlushChunks<'a> {
+    pub(crate) fn new(
+        buffer: &'a [u8],
+        display_size: DisplaySize,
+        stop_scroll: bool,
+        set_horizontal: bool,
+    ) -> Self {
+        Self {
+            buffer,
+            display_size,
+            stop_scroll,
+            set_horizontal,
+            page: 0,
+        }
+    }
+
+    fn chunk(&self, page: u8) -> Result<FlushChunk<'a>, Infallible> {
+        let (display_width, _) = self.display_size.dimensions();
+        let start = page as usize * display_width as usize;
+        let mut commands = Commands {
+            buf: [0; MAX_COMMANDS],
+            len: 0,
+        };
+
+        // Whatever `flush()` would do to get the display ready goes out with the first page
+        if page == 0 {
+            if self.stop_scroll {
+                Command::EnableScroll(false).send(&mut commands)?;
+            }
+
+            if self.set_horizontal {
+                Command::AddressMode(AddrMode::Horizontal).send(&mut commands)?;
+            }
+        }
+
+        send_column_address(self.display_size, 0, display_width - 1, &mut commands)?;
+        Command::PageAddress((page * 8).into(), (page * 8).into()).send(&mut commands)?;
+
+        Ok(FlushChunk {
+            commands: commands.buf,
+            commands_len: commands.len,
+            data: &self.buffer[start..start + display_width as usize],
+        })
+    }
+}
+
+impl<'a> Iterator for FlushChunks<'a> {
+    type Item = FlushChunk<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (_, display_height) = self.display_size.dimensions();
+
+        if self.page >= display_height / 8 {
+            return None;
+        }
+
+        let page = self.page;
+        self.page += 1;
+
+        match self.chunk(page) {
+            Ok(chunk) => Some(chunk),
+            Err(never) => match never {},
+        }
+    }
+}
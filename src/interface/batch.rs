@@ -0,0 +1,59 @@
+//! Command batching
+
+use super::DisplayInterface;
+
+/// Collects command bytes sent through it and passes them on to the wrapped interface in as few
+/// `send_commands()` calls as possible. Call `finish()` to send whatever is still pending.
+pub(crate) struct CommandBatch<'a, DI: 'a> {
+    iface: &'a mut DI,
+    buf: [u8; 32],
+    len: usize,
+}
+
+impl<'a, DI> CommandBatch<'a, DI>
+where
+    DI: DisplayInterface,
+{
+    /// Start a new, empty batch
+    pub(crate) fn new(iface: &'a mut DI) -> Self {
+        Self {
+            iface,
+            buf: [0; 32],
+            len: 0,
+        }
+    }
+
+    /// Send all pending command bytes
+    pub(crate) fn finish(&mut self) -> Result<(), DI::Error> {
+        if self.len > 0 {
+            self.iface.send_commands(&self.buf[..self.len])?;
+            self.len = 0;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a, DI> DisplayInterface for CommandBatch<'a, DI>
+where
+    DI: DisplayInterface,
+{
+    type Error = DI::Error;
+
+    fn send_command(&mut self, cmd: u8) -> Result<(), DI::Error> {
+        if self.len == self.buf.len() {
+            self.finish()?;
+        }
+
+        self.buf[self.len] = cmd;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), DI::Error> {
+        // Keep commands and data in the order they were sent
+        self.finish()?;
+        self.iface.send_data(buf)
+    }
+}
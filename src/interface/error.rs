@@ -0,0 +1,14 @@
+//! Interface error type
+
+use core::convert::Infallible;
+
+/// Errors that can occur in a display interface
+#[derive(Debug)]
+pub enum InterfaceError<CommE, PinE = Infallible> {
+    /// Communication error from the I2C or SPI peripheral
+    Comm(CommE),
+    /// Error setting the D/C pin
+    Pin(PinE),
+    /// The maximum transaction size set on the interface is too small to send anything
+    TransactionTooLong,
+}
@@ -3,37 +3,122 @@
 use hal;
 
 use super::DisplayInterface;
+use super::InterfaceError;
 
 /// SSD1306 I2C communication interface
 pub struct I2cInterface<I2C> {
     i2c: I2C,
     addr: u8,
+    retries: u8,
+    retries_used: u32,
+    max_transaction_bytes: usize,
 }
 
 impl<I2C> I2cInterface<I2C>
 where
-    I2C: hal::blocking::i2c::Write,
+    I2C: hal::i2c::I2c,
 {
     /// Create new SSD1306 I2C interface
     pub fn new(i2c: I2C, addr: u8) -> Self {
-        Self { i2c, addr }
+        Self {
+            i2c,
+            addr,
+            retries: 0,
+            retries_used: 0,
+            max_transaction_bytes: usize::MAX,
+        }
+    }
+
+    /// Retry each failed I2C transaction up to `retries` times before returning the error. Useful
+    /// on long or noisy buses. Defaults to 0, i.e. no retries.
+    pub fn with_retries(self, retries: u8) -> Self {
+        Self { retries, ..self }
+    }
+
+    /// Get the total number of retries used so far. A count that keeps going up means the bus is
+    /// regularly glitching, and tells you how close the `with_retries()` setting is to running out.
+    /// Saturates at `u32::MAX`.
+    pub fn retries_used(&self) -> u32 {
+        self.retries_used
+    }
+
+    /// Limit the number of bytes sent in a single I2C transaction, including the control byte.
+    /// Data and command streams are split into transactions that fit, and a limit below 2 bytes
+    /// returns `InterfaceError::TransactionTooLong` without sending anything. Defaults to no
+    /// limit, though writes are split into 17 byte transactions anyway unless a larger scratch
+    /// buffer is passed to `send_data_with_scratch()`.
+    pub fn with_max_transaction_bytes(self, max_transaction_bytes: usize) -> Self {
+        Self {
+            max_transaction_bytes,
+            ..self
+        }
+    }
+
+    /// Destroy the interface and return the I2C peripheral
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    /// Send a single I2C transaction, retrying on error
+    fn write(&mut self, bytes: &[u8]) -> Result<(), InterfaceError<I2C::Error>> {
+        if bytes.len() > self.max_transaction_bytes {
+            return Err(InterfaceError::TransactionTooLong);
+        }
+
+        let mut attempts_left = self.retries;
+
+        loop {
+            match self.i2c.write(self.addr, bytes) {
+                Err(_) if attempts_left > 0 => {
+                    attempts_left -= 1;
+                    self.retries_used = self.retries_used.saturating_add(1);
+                }
+                result => return result.map_err(InterfaceError::Comm),
+            }
+        }
+    }
+
+    /// Number of command or data bytes that fit in one transaction after the control byte, capped
+    /// at `len`
+    fn chunk_len(&self, len: usize) -> Result<usize, InterfaceError<I2C::Error>> {
+        match self.max_transaction_bytes.min(len) {
+            0 | 1 => Err(InterfaceError::TransactionTooLong),
+            n => Ok(n - 1),
+        }
     }
 }
 
 impl<I2C> DisplayInterface for I2cInterface<I2C>
 where
-    I2C: hal::blocking::i2c::Write,
+    I2C: hal::i2c::I2c,
 {
-    type Error = I2C::Error;
+    type Error = InterfaceError<I2C::Error>;
 
-    fn send_command(&mut self, cmd: u8) -> Result<(), I2C::Error> {
-        self.i2c.write(self.addr, &[0, cmd])?;
+    fn send_command(&mut self, cmd: u8) -> Result<(), Self::Error> {
+        self.write(&[0, cmd])?;
 
         Ok(())
     }
 
-    fn send_data(&mut self, buf: &[u8]) -> Result<(), I2C::Error> {
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
         let mut writebuf: [u8; 17] = [0; 17];
+        let chunk_len = self.chunk_len(writebuf.len())?;
+
+        // Command stream: a single control byte with Co = 0 followed by any number of commands
+        // 8.1.5.2 5) b) in the datasheet
+        writebuf[0] = 0x00;
+
+        for chunk in cmds.chunks(chunk_len) {
+            writebuf[1..1 + chunk.len()].copy_from_slice(chunk);
+            self.write(&writebuf[..1 + chunk.len()])?;
+        }
+
+        Ok(())
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        let mut writebuf: [u8; 17] = [0; 17];
+        let chunk_len = self.chunk_len(writebuf.len())?;
 
         // Data mode
         // 8.1.5.2 5) b) in the datasheet
@@ -44,11 +129,34 @@ where
             return Ok(());
         }
 
-        for chunk in buf.chunks(16) {
+        for chunk in buf.chunks(chunk_len) {
             for (i, byte) in chunk.iter().enumerate() {
                 writebuf[i + 1] = *byte;
             }
-            self.i2c.write(self.addr, &writebuf[..1 + chunk.len()])?;
+            self.write(&writebuf[..1 + chunk.len()])?;
+        }
+
+        Ok(())
+    }
+
+    fn send_data_with_scratch(
+        &mut self,
+        buf: &[u8],
+        scratch: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        // Need room for the control byte and at least one data byte
+        if scratch.len() < 2 {
+            return self.send_data(buf);
+        }
+
+        let chunk_len = self.chunk_len(scratch.len())?;
+
+        // Data mode
+        scratch[0] = 0x40;
+
+        for chunk in buf.chunks(chunk_len) {
+            scratch[1..1 + chunk.len()].copy_from_slice(chunk);
+            self.write(&scratch[..1 + chunk.len()])?;
         }
 
         Ok(())
@@ -0,0 +1,65 @@
+//! SSD1306 interface wrapper that reports every transfer
+
+use super::DisplayInterface;
+
+/// A transfer about to be sent to the display
+#[derive(Debug, Clone, Copy)]
+pub enum Transfer<'a> {
+    /// Command bytes
+    Command(&'a [u8]),
+    /// Display data bytes
+    Data(&'a [u8]),
+}
+
+/// Display interface wrapper that calls a hook with every transfer before passing it on to the
+/// wrapped interface, e.g. to log the exact bytes sent during bring-up. The bytes on the wire are
+/// the same as without the wrapper. Pass it to `Builder::connect()` or `SSD1306::new()` like any
+/// other interface.
+pub struct LoggingInterface<DI, F> {
+    iface: DI,
+    hook: F,
+}
+
+impl<DI, F> LoggingInterface<DI, F>
+where
+    DI: DisplayInterface,
+    F: FnMut(Transfer),
+{
+    /// Wrap `iface`, calling `hook` before each transfer
+    pub fn new(iface: DI, hook: F) -> Self {
+        Self { iface, hook }
+    }
+
+    /// Destroy the wrapper and return the wrapped interface
+    pub fn release(self) -> DI {
+        self.iface
+    }
+}
+
+impl<DI, F> DisplayInterface for LoggingInterface<DI, F>
+where
+    DI: DisplayInterface,
+    F: FnMut(Transfer),
+{
+    type Error = DI::Error;
+
+    fn send_command(&mut self, cmd: u8) -> Result<(), DI::Error> {
+        (self.hook)(Transfer::Command(&[cmd]));
+        self.iface.send_command(cmd)
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), DI::Error> {
+        (self.hook)(Transfer::Command(cmds));
+        self.iface.send_commands(cmds)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), DI::Error> {
+        (self.hook)(Transfer::Data(buf));
+        self.iface.send_data(buf)
+    }
+
+    fn send_data_with_scratch(&mut self, buf: &[u8], scratch: &mut [u8]) -> Result<(), DI::Error> {
+        (self.hook)(Transfer::Data(buf));
+        self.iface.send_data_with_scratch(buf, scratch)
+    }
+}
@@ -1,7 +1,12 @@
 //! SSD1306 Communication Interface
 
+mod batch;
+mod error;
 pub mod i2c;
+#[cfg(feature = "debug-hooks")]
+pub mod logging;
 pub mod spi;
+pub mod spi3wire;
 
 /// A method of communicating with SSD1306
 pub trait DisplayInterface {
@@ -10,9 +15,33 @@ pub trait DisplayInterface {
 
     /// Send a command to display.
     fn send_command(&mut self, cmd: u8) -> Result<(), Self::Error>;
+    /// Send several commands to display. Interfaces that can send them in a single transaction
+    /// should override this; the default sends them one at a time.
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        for cmd in cmds {
+            self.send_command(*cmd)?;
+        }
+
+        Ok(())
+    }
     /// Send data to display.
     fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error>;
+    /// Send data to display, building transactions in `scratch` instead of a buffer on the stack.
+    /// Interfaces that don't need a transaction buffer should leave this as is; the default
+    /// ignores `scratch` and calls `send_data()`.
+    fn send_data_with_scratch(
+        &mut self,
+        buf: &[u8],
+        _scratch: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.send_data(buf)
+    }
 }
 
+pub(crate) use self::batch::CommandBatch;
+pub use self::error::InterfaceError;
 pub use self::i2c::I2cInterface;
+#[cfg(feature = "debug-hooks")]
+pub use self::logging::{LoggingInterface, Transfer};
 pub use self::spi::SpiInterface;
+pub use self::spi3wire::Spi3WireInterface;
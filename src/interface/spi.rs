@@ -4,6 +4,7 @@ use hal;
 use hal::digital::OutputPin;
 
 use super::DisplayInterface;
+use super::InterfaceError;
 
 /// SPI display interface.
 ///
@@ -11,41 +12,85 @@ use super::DisplayInterface;
 pub struct SpiInterface<SPI, DC> {
     spi: SPI,
     dc: DC,
+    max_transaction_bytes: usize,
 }
 
 impl<SPI, DC> SpiInterface<SPI, DC>
 where
-    SPI: hal::blocking::spi::Write<u8>,
+    SPI: hal::spi::SpiDevice,
     DC: OutputPin,
 {
     /// Create new SPI interface for communciation with SSD1306
     pub fn new(spi: SPI, dc: DC) -> Self {
-        Self { spi, dc }
+        Self {
+            spi,
+            dc,
+            max_transaction_bytes: usize::MAX,
+        }
+    }
+
+    /// Limit the number of bytes sent in a single SPI write, for HALs that would otherwise silently
+    /// truncate long writes. Longer command and data streams are split into writes that fit. A
+    /// limit of 0 returns `InterfaceError::TransactionTooLong` without sending anything. Defaults
+    /// to no limit, so that a full 128x64 frame goes out as a single 1024 byte write.
+    pub fn with_max_transaction_bytes(self, max_transaction_bytes: usize) -> Self {
+        Self {
+            max_transaction_bytes,
+            ..self
+        }
+    }
+
+    /// Destroy the interface and return the SPI device and data/command pin
+    pub fn release(self) -> (SPI, DC) {
+        (self.spi, self.dc)
+    }
+
+    /// Write `bytes` in as few SPI writes as the transaction limit allows
+    fn write(&mut self, bytes: &[u8]) -> Result<(), InterfaceError<SPI::Error, DC::Error>> {
+        if self.max_transaction_bytes == 0 {
+            return Err(InterfaceError::TransactionTooLong);
+        }
+
+        for chunk in bytes.chunks(self.max_transaction_bytes) {
+            self.spi.write(chunk).map_err(InterfaceError::Comm)?;
+        }
+
+        Ok(())
     }
 }
 
 impl<SPI, DC> DisplayInterface for SpiInterface<SPI, DC>
 where
-    SPI: hal::blocking::spi::Write<u8>,
+    SPI: hal::spi::SpiDevice,
     DC: OutputPin,
 {
-    type Error = SPI::Error;
+    type Error = InterfaceError<SPI::Error, DC::Error>;
+
+    fn send_command(&mut self, cmd: u8) -> Result<(), Self::Error> {
+        self.dc.set_low().map_err(InterfaceError::Pin)?;
+
+        self.write(&[cmd])?;
+
+        self.dc.set_high().map_err(InterfaceError::Pin)?;
+
+        Ok(())
+    }
 
-    fn send_command(&mut self, cmd: u8) -> Result<(), SPI::Error> {
-        self.dc.set_low();
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        self.dc.set_low().map_err(InterfaceError::Pin)?;
 
-        self.spi.write(&[cmd])?;
+        self.write(cmds)?;
 
-        self.dc.set_high();
+        self.dc.set_high().map_err(InterfaceError::Pin)?;
 
         Ok(())
     }
 
-    fn send_data(&mut self, buf: &[u8]) -> Result<(), SPI::Error> {
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
         // 1 = data, 0 = command
-        self.dc.set_high();
+        self.dc.set_high().map_err(InterfaceError::Pin)?;
 
-        self.spi.write(&buf)?;
+        self.write(buf)?;
 
         Ok(())
     }
@@ -0,0 +1,89 @@
+//! SSD1306 3-wire SPI interface
+
+use hal;
+
+use super::DisplayInterface;
+use super::InterfaceError;
+
+/// 3-wire SPI display interface.
+///
+/// 3-wire SPI has no D/C pin. Instead, every byte is preceded by a D/C bit, making each transfer a
+/// 9 bit word. The SPI peripheral must be configured for 9 bit frames, sending the low 9 bits of
+/// each `u16` word MSB first. Not all SPI peripherals can do this; check your HAL before wiring up
+/// a 3-wire module.
+pub struct Spi3WireInterface<SPI> {
+    spi: SPI,
+    max_transaction_bytes: usize,
+}
+
+impl<SPI> Spi3WireInterface<SPI>
+where
+    SPI: hal::spi::SpiDevice<u16>,
+{
+    /// Create new 3-wire SPI interface for communication with SSD1306
+    pub fn new(spi: SPI) -> Self {
+        Self {
+            spi,
+            max_transaction_bytes: usize::MAX,
+        }
+    }
+
+    /// Limit the number of bytes, i.e. 9 bit words, sent in a single SPI write. Writes are split
+    /// into transactions of at most 16 words anyway, so this only matters for limits below that.
+    /// A limit of 0 returns `InterfaceError::TransactionTooLong` without sending anything.
+    /// Defaults to no limit.
+    pub fn with_max_transaction_bytes(self, max_transaction_bytes: usize) -> Self {
+        Self {
+            max_transaction_bytes,
+            ..self
+        }
+    }
+
+    /// Destroy the interface and return the SPI device
+    pub fn release(self) -> SPI {
+        self.spi
+    }
+
+    /// Send `bytes` as words with the D/C bit set to `dc`, split into transactions that fit the
+    /// stack buffer and the transaction limit
+    fn write(&mut self, bytes: &[u8], dc: u16) -> Result<(), InterfaceError<SPI::Error>> {
+        let mut writebuf: [u16; 16] = [0; 16];
+
+        if self.max_transaction_bytes == 0 {
+            return Err(InterfaceError::TransactionTooLong);
+        }
+
+        for chunk in bytes.chunks(self.max_transaction_bytes.min(writebuf.len())) {
+            for (i, byte) in chunk.iter().enumerate() {
+                writebuf[i] = dc | u16::from(*byte);
+            }
+            self.spi
+                .write(&writebuf[..chunk.len()])
+                .map_err(InterfaceError::Comm)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<SPI> DisplayInterface for Spi3WireInterface<SPI>
+where
+    SPI: hal::spi::SpiDevice<u16>,
+{
+    type Error = InterfaceError<SPI::Error>;
+
+    fn send_command(&mut self, cmd: u8) -> Result<(), Self::Error> {
+        // D/C bit cleared = command
+        self.write(&[cmd], 0)
+    }
+
+    fn send_commands(&mut self, cmds: &[u8]) -> Result<(), Self::Error> {
+        // D/C bit cleared = command
+        self.write(cmds, 0)
+    }
+
+    fn send_data(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        // D/C bit set = data
+        self.write(buf, 0x100)
+    }
+}
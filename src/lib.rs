@@ -15,26 +15,74 @@
 
 #[cfg(feature = "graphics")]
 extern crate embedded_graphics;
+#[cfg(feature = "graphics-core")]
+extern crate embedded_graphics_core;
 extern crate embedded_hal as hal;
+#[cfg(test)]
+#[macro_use]
+extern crate std;
 
+#[cfg(feature = "unstable-commands")]
+pub mod command;
+#[cfg(not(feature = "unstable-commands"))]
 mod command;
+mod config;
+mod displayrotation;
 mod displaysize;
+mod error;
+mod flushchunks;
+mod pattern;
 pub mod builder;
 pub mod interface;
 
 pub use builder::Builder;
+pub use command::{AddrMode, HScrollDir, ScrollInterval, VcomhLevel};
+pub use displayrotation::DisplayRotation;
 pub use displaysize::DisplaySize;
-use command::{AddrMode, Command, VcomhLevel};
+pub use error::Error;
+pub use flushchunks::{FlushChunk, FlushChunks};
+pub use pattern::Pattern;
+use command::Command;
+use config::{clamp_multiplex, DisplayConfig};
 
-use hal::blocking::delay::DelayMs;
+use hal::delay::DelayNs;
 use hal::digital::OutputPin;
-use interface::DisplayInterface;
+use interface::{CommandBatch, DisplayInterface};
+
+/// Size of the driver's own framebuffer, enough for the largest display the SSD1306 can drive
+const BUFFER_SIZE: usize = 128 * 64 / 8;
 
-/// SSD1306
-pub struct SSD1306<DI> {
+/// SSD1306 driver
+///
+/// By default the driver draws into its own 1KiB framebuffer in RAM, which is written out to the
+/// display with `flush()`. The full buffer is allocated regardless of display size.
+///
+/// The framebuffer already acts as a back buffer: drawing never touches the display until
+/// `flush()`, so a frame can be built up over several draw calls and shown all at once.
+///
+/// Use [`new_with_buffer`](#method.new_with_buffer) or `Builder::connect_with_buffer()` to draw
+/// into a caller-provided buffer instead.
+pub struct SSD1306<DI, BUF = [u8; BUFFER_SIZE]> {
     iface: DI,
-    buffer: [u8; 1024],
+    buffer: BUF,
     display_size: DisplaySize,
+    config: DisplayConfig,
+    dirty: bool,
+    /// Copy of the frame last sent to the display, used to only send changed bytes
+    #[cfg(feature = "diff-flush")]
+    sent: [u8; BUFFER_SIZE],
+    /// Whether `sent` matches the display RAM
+    #[cfg(feature = "diff-flush")]
+    sent_valid: bool,
+    /// Tile size in (columns, pages) that changes are rounded up to by `flush_diff()`
+    #[cfg(feature = "diff-flush")]
+    flush_granularity: (u8, u8),
+    last_flush_ms: Option<u32>,
+    addr_mode: AddrMode,
+    /// Clip rectangle as (x start, y start, x end, y end), end exclusive
+    clip: Option<(u32, u32, u32, u32)>,
+    display_on: bool,
+    scrolling: bool,
 }
 
 impl<DI> SSD1306<DI>
@@ -43,119 +91,2167 @@ where
 {
     /// Create new SSD1306 instance
     pub fn new(iface: DI, display_size: DisplaySize) -> SSD1306<DI> {
+        Self::with_config(iface, display_size, DisplayConfig::default())
+    }
+
+    pub(crate) fn with_config(
+        iface: DI,
+        display_size: DisplaySize,
+        config: DisplayConfig,
+    ) -> SSD1306<DI> {
+        SSD1306::from_parts(iface, display_size, [0; BUFFER_SIZE], config)
+    }
+}
+
+impl<'a, DI> SSD1306<DI, &'a mut [u8]>
+where
+    DI: DisplayInterface,
+{
+    /// Create new SSD1306 instance drawing into a caller-provided framebuffer, e.g. one placed in a
+    /// specific memory region. The buffer must hold at least `width * height / 8` bytes for the
+    /// display size, or `Error::InvalidArgument` is returned. Its contents are used as is, so clear
+    /// it first if needed.
+    pub fn new_with_buffer(
+        iface: DI,
+        display_size: DisplaySize,
+        buffer: &'a mut [u8],
+    ) -> Result<Self, Error<DI::Error>> {
+        Self::with_buffer_and_config(iface, display_size, buffer, DisplayConfig::default())
+    }
+
+    pub(crate) fn with_buffer_and_config(
+        iface: DI,
+        display_size: DisplaySize,
+        buffer: &'a mut [u8],
+        config: DisplayConfig,
+    ) -> Result<Self, Error<DI::Error>> {
+        let (display_width, display_height) = display_size.dimensions();
+
+        if buffer.len() < display_width as usize * display_height as usize / 8 {
+            return Err(Error::InvalidArgument);
+        }
+
+        Ok(SSD1306::from_parts(iface, display_size, buffer, config))
+    }
+}
+
+impl<DI, BUF> SSD1306<DI, BUF>
+where
+    DI: DisplayInterface,
+    BUF: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn from_parts(
+        iface: DI,
+        display_size: DisplaySize,
+        buffer: BUF,
+        config: DisplayConfig,
+    ) -> Self {
         SSD1306 {
             iface,
             display_size,
-            buffer: [0; 1024],
+            config,
+            buffer,
+            dirty: true,
+            #[cfg(feature = "diff-flush")]
+            sent: [0; BUFFER_SIZE],
+            #[cfg(feature = "diff-flush")]
+            sent_valid: false,
+            #[cfg(feature = "diff-flush")]
+            flush_granularity: (1, 1),
+            last_flush_ms: None,
+            addr_mode: AddrMode::Horizontal,
+            clip: None,
+            display_on: false,
+            scrolling: false,
         }
     }
 
     /// Clear the display buffer. You need to call `disp.flush()` for any effect on the screen
-    pub fn clear(&mut self) {
-        self.buffer = [0; 1024];
+    pub fn clear_buffer(&mut self) {
+        for byte in self.buffer.as_mut() {
+            *byte = 0;
+        }
+
+        self.dirty = true;
+    }
+
+    /// Change the display rotation. This sends the matching segment remap and COM scan direction,
+    /// unless overridden with `set_segment_remap()` or `set_reverse_com_dir()`, and changes how
+    /// drawing coordinates map to the framebuffer. Existing buffer contents aren't moved, so clear
+    /// and redraw after switching between landscape and portrait.
+    pub fn set_rotation(&mut self, rotation: DisplayRotation) -> Result<(), DI::Error> {
+        self.config.rotation = rotation;
+
+        send_rotation(&self.config, &mut self.iface)
+    }
+
+    /// Get the current rotation
+    pub fn rotation(&self) -> DisplayRotation {
+        self.config.rotation
+    }
+
+    /// Get the display dimensions as seen by drawing code, taking rotation into account
+    pub fn dimensions(&self) -> (u8, u8) {
+        self.display_size.dimensions_for(self.config.rotation)
+    }
+
+    /// Get a reference to the display interface, e.g. to read out `I2cInterface::retries_used()`
+    pub fn interface(&self) -> &DI {
+        &self.iface
+    }
+
+    /// Destroy the driver and return the interface along with the size and rotation it was set
+    /// up with, e.g. to reconfigure the bus and create the driver again. The framebuffer contents
+    /// are dropped.
+    pub fn release(self) -> (DI, DisplaySize, DisplayRotation) {
+        (self.iface, self.display_size, self.config.rotation)
+    }
+
+    /// Get the part of the framebuffer used by the display. Each byte is a column of 8 pixels, with
+    /// the least significant bit at the top. Bytes run left to right, then page by page downwards.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer.as_ref()[..self.buffer_len()]
+    }
+
+    /// Get mutable access to the part of the framebuffer used by the display. The caller is
+    /// responsible for writing data in the display's page layout; see
+    /// [`buffer`](#method.buffer). The buffer is marked as changed, so the next `flush()` sends
+    /// it.
+    pub fn buffer_mut(&mut self) -> &mut [u8] {
+        let len = self.buffer_len();
+
+        self.dirty = true;
+
+        &mut self.buffer.as_mut()[..len]
+    }
+
+    /// Fill the framebuffer with a test pattern, e.g. to check contrast and orientation on the
+    /// bench. The pattern is written in display RAM layout, ignoring rotation. You need to call
+    /// `flush()` for any effect on the screen.
+    pub fn fill_pattern(&mut self, pattern: Pattern) {
+        let width = self.display_size.width() as usize;
+        let len = self.buffer_len();
+
+        for (i, byte) in self.buffer.as_mut()[..len].iter_mut().enumerate() {
+            *byte = pattern.byte(i % width, i / width, width);
+        }
+
+        self.dirty = true;
+    }
+
+    /// Get a 32 bit FNV-1a hash of the part of the framebuffer used by the display. Store it after
+    /// flushing and compare it with the next frame to cheaply detect whether anything changed.
+    /// Different frames can hash to the same value, although that's very unlikely.
+    pub fn buffer_checksum(&self) -> u32 {
+        self.buffer().iter().fold(0x811c_9dc5, |hash, byte| {
+            (hash ^ u32::from(*byte)).wrapping_mul(0x0100_0193)
+        })
     }
 
     /// Reset display
-    pub fn reset<RST, DELAY>(&mut self, rst: &mut RST, delay: &mut DELAY)
+    ///
+    /// This is optional: `init()` doesn't depend on it. Boards that tie the display's reset line
+    /// to the MCU reset have no pin to toggle and should just call `init()`.
+    pub fn reset<RST, DELAY>(&mut self, rst: &mut RST, delay: &mut DELAY) -> Result<(), RST::Error>
     where
         RST: OutputPin,
-        DELAY: DelayMs<u8>,
+        DELAY: DelayNs,
     {
-        rst.set_high();
+        rst.set_high()?;
         delay.delay_ms(1);
-        rst.set_low();
+        rst.set_low()?;
         delay.delay_ms(10);
-        rst.set_high();
+        rst.set_high()
+    }
+
+    /// Reset display using the minimum timings from the datasheet: a 3µs low pulse followed by a
+    /// 100µs settling time. This is much faster than [`reset`](#method.reset), which helps when
+    /// bringing up several displays, but needs a delay provider accurate to a few microseconds.
+    pub fn reset_us<RST, DELAY>(
+        &mut self,
+        rst: &mut RST,
+        delay: &mut DELAY,
+    ) -> Result<(), RST::Error>
+    where
+        RST: OutputPin,
+        DELAY: DelayNs,
+    {
+        rst.set_low()?;
+        delay.delay_us(3);
+        rst.set_high()?;
+        delay.delay_us(100);
+
+        Ok(())
+    }
+
+    /// Reset the display, wait for it to come out of reset and initialize it. Equivalent to calling
+    /// [`reset`](#method.reset) followed by [`init`](#method.init) with the required delay in
+    /// between.
+    pub fn reset_and_init<RST, DELAY>(
+        &mut self,
+        rst: &mut RST,
+        delay: &mut DELAY,
+    ) -> Result<(), Error<DI::Error, RST::Error>>
+    where
+        RST: OutputPin,
+        DELAY: DelayNs,
+    {
+        self.reset(rst, delay).map_err(Error::Pin)?;
+        delay.delay_ms(1);
+        self.init().map_err(Error::Comm)
+    }
+
+    /// Run a visual self test for bring-up: all pixels on, all pixels off, an 8x8 checkerboard,
+    /// the checkerboard inverted and a contrast sweep, with short pauses in between. The display
+    /// must be initialised. Contrast and inversion are restored afterwards, but the framebuffer is
+    /// left cleared.
+    pub fn self_test<DELAY>(&mut self, delay: &mut DELAY) -> Result<(), DI::Error>
+    where
+        DELAY: DelayNs,
+    {
+        let contrast = self.config.contrast;
+        let inverted = self.config.inverted;
+
+        self.set_all_on(true)?;
+        delay.delay_ms(500);
+        self.set_all_on(false)?;
+
+        self.clear_buffer();
+        self.force_flush()?;
+        delay.delay_ms(500);
+
+        self.fill_pattern(Pattern::Checkerboard);
+        self.force_flush()?;
+        delay.delay_ms(500);
+
+        self.set_invert(!inverted)?;
+        delay.delay_ms(500);
+        self.set_invert(inverted)?;
+
+        for level in (0..=255u8).step_by(17) {
+            self.set_contrast(level)?;
+            delay.delay_ms(30);
+        }
+
+        self.set_contrast(contrast)?;
+
+        self.clear_buffer();
+        self.force_flush()?;
+
+        Ok(())
+    }
+
+    /// Write out data to display. This is a noop if the buffer hasn't changed since the last flush.
+    /// Returns the number of data bytes sent, not counting addressing commands.
+    ///
+    /// With the `diff-flush` feature enabled, only the bytes that changed since the last flush are
+    /// sent, at the cost of another 1KiB of RAM for a copy of the last sent frame.
+    pub fn flush(&mut self) -> Result<usize, DI::Error> {
+        // Stopping a scroll invalidates the display RAM, so do it before deciding what to send
+        self.stop_scroll()?;
+
+        if !self.dirty {
+            return Ok(0);
+        }
+
+        #[cfg(feature = "diff-flush")]
+        {
+            if self.sent_valid {
+                return self.flush_diff();
+            }
+        }
+
+        self.force_flush()
+    }
+
+    /// Flush the buffer, unless the last flush through this method was less than
+    /// `min_interval_ms` before `now_ms`. `now_ms` is a millisecond timestamp from any free
+    /// running timer; wrap-around is handled. Returns whether any data was sent.
+    pub fn flush_throttled(
+        &mut self,
+        now_ms: u32,
+        min_interval_ms: u32,
+    ) -> Result<bool, DI::Error> {
+        if let Some(last) = self.last_flush_ms {
+            if now_ms.wrapping_sub(last) < min_interval_ms {
+                return Ok(false);
+            }
+        }
+
+        if !self.dirty {
+            return Ok(false);
+        }
+
+        let sent = self.flush()?;
+        self.last_flush_ms = Some(now_ms);
+
+        Ok(sent > 0)
+    }
+
+    /// Get an iterator over the framebuffer one page at a time. Each `FlushChunk` carries the
+    /// addressing commands and data for one page, for the caller to send at its own pace, e.g.
+    /// with `FlushChunk::send()`, over DMA or on an async bus. Unlike `flush()`, all pages are
+    /// included whether or not the buffer changed. `flush()` itself sends the frame in a single
+    /// window instead, which saves the addressing commands for every page after the first.
+    ///
+    /// The driver can't tell whether the chunks are actually sent, so it treats the frame as sent
+    /// right away: the buffer is marked clean, and any scroll stop or addressing mode switch the
+    /// first chunk carries is taken as done. Send every chunk in order, or call `force_flush()`
+    /// if that didn't happen.
+    pub fn flush_chunks(&mut self) -> FlushChunks<'_> {
+        let stop_scroll = self.scrolling;
+        let set_horizontal = !matches!(self.addr_mode, AddrMode::Horizontal);
+        let len = self.buffer_len();
+
+        self.scrolling = false;
+        self.addr_mode = AddrMode::Horizontal;
+        self.dirty = false;
+
+        #[cfg(feature = "diff-flush")]
+        {
+            self.sent[..len].copy_from_slice(&self.buffer.as_ref()[..len]);
+            self.sent_valid = true;
+        }
+
+        FlushChunks::new(
+            &self.buffer.as_ref()[..len],
+            self.display_size,
+            stop_scroll,
+            set_horizontal,
+        )
     }
 
-    /// Write out data to display
-    pub fn flush(&mut self) -> Result<(), DI::Error> {
+    /// Set how `flush()` groups changed bytes with the `diff-flush` feature. The display is split
+    /// into tiles of `columns` by `pages`, and every tile with any change is sent whole, with
+    /// neighbouring changed tiles in the same band of pages sharing one column/page window.
+    /// Bigger tiles send some unchanged bytes along, but need fewer windows, each of which costs
+    /// six command bytes. With I2C that's often the better deal for scattered small changes.
+    /// Defaults to 1 by 1, i.e. only changed bytes are sent. Values of 0 are treated as 1.
+    #[cfg(feature = "diff-flush")]
+    pub fn set_flush_granularity(&mut self, columns: u8, pages: u8) {
+        self.flush_granularity = (columns.max(1), pages.max(1));
+    }
+
+    /// Send the tiles that differ from the last sent frame, each run of them in its own
+    /// column/page window
+    #[cfg(feature = "diff-flush")]
+    fn flush_diff(&mut self) -> Result<usize, DI::Error> {
+        let (display_width, display_height) = self.display_size.dimensions();
+        let width = display_width as usize;
+        let pages = display_height as usize / 8;
+        let (tile_columns, tile_pages) = self.flush_granularity;
+        let (tile_columns, tile_pages) = (tile_columns as usize, tile_pages as usize);
+        let mut sent = 0;
+
+        self.prepare_flush()?;
+
+        let buffer = self.buffer.as_ref();
+
+        for first_page in (0..pages).step_by(tile_pages) {
+            let last_page = (first_page + tile_pages).min(pages) - 1;
+            let tile_changed = |last_sent: &[u8], col: usize| {
+                let end = (col + tile_columns).min(width);
+
+                (first_page..=last_page).any(|page| {
+                    let row = page * width;
+
+                    buffer[row + col..row + end] != last_sent[row + col..row + end]
+                })
+            };
+            let mut col = 0;
+
+            while col < width {
+                if !tile_changed(&self.sent, col) {
+                    col += tile_columns;
+                    continue;
+                }
+
+                let start = col;
+
+                while col < width && tile_changed(&self.sent, col) {
+                    col += tile_columns;
+                }
+
+                let end = col.min(width);
+                let (start_row, end_row) = ((first_page * 8) as u8, (last_page * 8) as u8);
+
+                send_column_address(
+                    self.display_size,
+                    start as u8,
+                    (end - 1) as u8,
+                    &mut self.iface,
+                )?;
+                Command::PageAddress(start_row.into(), end_row.into()).send(&mut self.iface)?;
+
+                for page in first_page..=last_page {
+                    let run = page * width + start..page * width + end;
+
+                    self.iface.send_data(&buffer[run.clone()])?;
+                    self.sent[run.clone()].copy_from_slice(&buffer[run]);
+                    sent += end - start;
+                }
+            }
+        }
+
+        self.dirty = false;
+
+        Ok(sent)
+    }
+
+    /// Write out data to display, even if the buffer hasn't changed since the last flush. Useful if
+    /// the display RAM may have been disturbed, e.g. by a power glitch. Returns the number of data
+    /// bytes sent.
+    pub fn force_flush(&mut self) -> Result<usize, DI::Error> {
         let (display_width, display_height) = self.display_size.dimensions();
 
-        Command::ColumnAddress(0, display_width - 1).send(&mut self.iface)?;
+        self.prepare_flush()?;
+
+        send_column_address(self.display_size, 0, display_width - 1, &mut self.iface)?;
         Command::PageAddress(0.into(), (display_height - 1).into()).send(&mut self.iface)?;
 
-        match self.display_size {
-            DisplaySize::Display128x64 => self.iface.send_data(&self.buffer),
-            DisplaySize::Display128x32 => self.iface.send_data(&self.buffer[0..512]),
-            DisplaySize::Display96x16 => self.iface.send_data(&self.buffer[0..192]),
+        let len = self.buffer_len();
+        let data = &self.buffer.as_ref()[..len];
+
+        self.iface.send_data(data)?;
+
+        let sent = data.len();
+
+        #[cfg(feature = "diff-flush")]
+        {
+            self.sent[..sent].copy_from_slice(data);
+            self.sent_valid = true;
         }
+
+        self.dirty = false;
+
+        Ok(sent)
     }
 
-    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
-    //// coordinates are out of the bounds of the display, this method call is a noop.
-    pub fn set_pixel(&mut self, x: u32, y: u32, value: u8) {
-        let (display_width, _) = self.display_size.dimensions();
+    /// Like [`flush`](#method.flush), but the interface builds its transactions in `scratch`
+    /// instead of a buffer on the stack. On I2C each transaction carries up to `scratch.len() - 1`
+    /// data bytes, so a bigger scratch buffer also means fewer transactions, up to the interface's
+    /// maximum transaction size. The whole frame is sent, even with the `diff-flush` feature.
+    /// Returns the number of data bytes sent, or `Error::InvalidArgument` if `scratch` is shorter
+    /// than 2 bytes.
+    pub fn flush_with_scratch(&mut self, scratch: &mut [u8]) -> Result<usize, Error<DI::Error>> {
+        if scratch.len() < 2 {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.stop_scroll().map_err(Error::Comm)?;
 
-        let idx = ((y as usize) / 8 * display_width as usize) + (x as usize);
+        if !self.dirty {
+            return Ok(0);
+        }
 
-        if idx < self.buffer.len() {
-            let byte = &mut self.buffer[idx];
-            let bit = 1 << (y % 8);
+        let (display_width, display_height) = self.display_size.dimensions();
 
-            if value == 0 {
-                *byte &= !bit;
-            } else {
-                *byte |= bit;
-            }
+        self.prepare_flush().map_err(Error::Comm)?;
+
+        send_column_address(self.display_size, 0, display_width - 1, &mut self.iface)
+            .map_err(Error::Comm)?;
+        Command::PageAddress(0.into(), (display_height - 1).into())
+            .send(&mut self.iface)
+            .map_err(Error::Comm)?;
+
+        let len = self.buffer_len();
+        let buffer = self.buffer.as_ref();
+
+        self.iface
+            .send_data_with_scratch(&buffer[..len], scratch)
+            .map_err(Error::Comm)?;
+
+        #[cfg(feature = "diff-flush")]
+        {
+            self.sent[..len].copy_from_slice(&buffer[..len]);
+            self.sent_valid = true;
         }
+
+        self.dirty = false;
+
+        Ok(len)
     }
 
-    // Display is set up in column mode, i.e. a byte walks down a column of 8 pixels from column 0 on the left, to column _n_ on the right
-    /// Initialize display in column mode.
-    pub fn init(&mut self) -> Result<(), DI::Error> {
+    /// Turn the display on or off. The display RAM and framebuffer contents are kept while off.
+    pub fn display_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        Command::DisplayOn(on).send(&mut self.iface)?;
+        self.display_on = on;
+
+        Ok(())
+    }
+
+    /// Put the display into its low power state by turning it off and then disabling the charge
+    /// pump. The display RAM and framebuffer are kept. Call [`wake`](#method.wake) to turn it
+    /// back on.
+    pub fn sleep(&mut self) -> Result<(), DI::Error> {
+        self.display_on(false)?;
+        Command::ChargePump(false).send(&mut self.iface)
+    }
+
+    /// Wake the display from [`sleep`](#method.sleep) by enabling the charge pump, if it's in use,
+    /// and then turning the display on.
+    pub fn wake(&mut self) -> Result<(), DI::Error> {
+        Command::ChargePump(self.config.charge_pump).send(&mut self.iface)?;
+        self.display_on(true)
+    }
+
+    /// Invert the display so that set pixels are dark and cleared pixels are lit
+    pub fn set_invert(&mut self, inverted: bool) -> Result<(), DI::Error> {
+        Command::Invert(inverted).send(&mut self.iface)?;
+        self.config.inverted = inverted;
+
+        Ok(())
+    }
+
+    /// Set the display contrast. Higher is brighter.
+    pub fn set_contrast(&mut self, contrast: u8) -> Result<(), DI::Error> {
+        Command::Contrast(contrast).send(&mut self.iface)?;
+        self.config.contrast = contrast;
+
+        Ok(())
+    }
+
+    /// Move the contrast one `step` towards `target`, for fading the display in or out. Call this
+    /// once per animation tick until it returns `true`, meaning `target` has been reached. A
+    /// `step` of 0 is treated as 1, so the fade always finishes.
+    pub fn fade_to_contrast(&mut self, target: u8, step: u8) -> Result<bool, DI::Error> {
+        let current = self.config.contrast;
+        let step = step.max(1);
+
+        let next = if current < target {
+            current.saturating_add(step).min(target)
+        } else {
+            current.saturating_sub(step).max(target)
+        };
+
+        if next != current {
+            self.set_contrast(next)?;
+        }
+
+        Ok(next == target)
+    }
+
+    /// Override the segment remap, i.e. whether column 0 is on the left (`true`) or the right
+    /// (`false`) edge of the panel, independently of the rotation. Together with
+    /// [`set_reverse_com_dir`](#method.set_reverse_com_dir) this fixes modules that are mounted
+    /// mirrored:
+    ///
+    /// * remap on, reverse COM on: upright, as with `Rotate0`
+    /// * remap off, reverse COM off: upside down, as with `Rotate180`
+    /// * remap off, reverse COM on: mirrored left to right
+    /// * remap on, reverse COM off: mirrored top to bottom
+    pub fn set_segment_remap(&mut self, remap: bool) -> Result<(), DI::Error> {
+        Command::SegmentRemap(remap).send(&mut self.iface)?;
+        self.config.segment_remap = Some(remap);
+
+        Ok(())
+    }
+
+    /// Override the COM scan direction, i.e. whether row 0 is at the top (`true`) or the bottom
+    /// (`false`) edge of the panel, independently of the rotation. See
+    /// [`set_segment_remap`](#method.set_segment_remap) for the common combinations.
+    pub fn set_reverse_com_dir(&mut self, reverse: bool) -> Result<(), DI::Error> {
+        Command::ReverseComDir(reverse).send(&mut self.iface)?;
+        self.config.reverse_com_dir = Some(reverse);
+
+        Ok(())
+    }
+
+    /// Override the COM pin hardware configuration. `alternative` selects alternative instead of
+    /// sequential COM pin wiring, and `left_right_remap` swaps the left and right halves of the
+    /// rows. Try changing these if every other row is blank or rows appear interleaved.
+    pub fn set_com_pin_config(
+        &mut self,
+        alternative: bool,
+        left_right_remap: bool,
+    ) -> Result<(), DI::Error> {
+        Command::ComPinConfig(alternative, left_right_remap).send(&mut self.iface)?;
+        self.config.com_pins = Some((alternative, left_right_remap));
+
+        Ok(())
+    }
+
+    /// Set the display clock divide ratio minus one and the oscillator frequency. Both are 4 bit
+    /// values; `Error::InvalidArgument` is returned and nothing is sent if either is above 15.
+    ///
+    /// The frame rate is roughly `Fosc / ((divide + 1) * (phase1 + phase2 + 50) * (multiplex + 1))`
+    /// where `phase1` and `phase2` are the pre-charge periods. `Fosc` increases with `osc_freq`
+    /// and is around 370kHz at the default of 8, giving about 88Hz on a 128x64 display with the
+    /// default settings. Lower the frame rate to save power, or change it to reduce flicker on
+    /// camera.
+    pub fn set_display_clock(&mut self, divide: u8, osc_freq: u8) -> Result<(), Error<DI::Error>> {
+        if divide > 15 || osc_freq > 15 {
+            return Err(Error::InvalidArgument);
+        }
+
+        Command::DisplayClockDiv(osc_freq, divide)
+            .send(&mut self.iface)
+            .map_err(Error::Comm)?;
+        self.config.clock_divide = divide;
+        self.config.osc_freq = osc_freq;
+
+        Ok(())
+    }
+
+    /// Set the pre-charge period phase 1 and phase 2 lengths in display clocks. Each value must be
+    /// between 1 and 15, otherwise `Error::InvalidArgument` is returned and nothing is sent.
+    pub fn set_precharge(&mut self, phase1: u8, phase2: u8) -> Result<(), Error<DI::Error>> {
+        if phase1 == 0 || phase1 > 15 || phase2 == 0 || phase2 > 15 {
+            return Err(Error::InvalidArgument);
+        }
+
+        Command::PreChargePeriod(phase1, phase2)
+            .send(&mut self.iface)
+            .map_err(Error::Comm)?;
+        self.config.precharge = (phase1, phase2);
+
+        Ok(())
+    }
+
+    /// Set the VCOMH deselect level. A higher level can help panels that look washed out.
+    pub fn set_vcomh(&mut self, level: VcomhLevel) -> Result<(), DI::Error> {
+        Command::VcomhDeselect(level).send(&mut self.iface)?;
+        self.config.vcomh = level;
+
+        Ok(())
+    }
+
+    /// Set the display RAM row shown at the top of the display, panning the visible window
+    /// vertically without sending any display data. The value is clamped to 63.
+    pub fn set_start_line(&mut self, line: u8) -> Result<(), DI::Error> {
+        let line = line.min(63);
+
+        Command::StartLine(line).send(&mut self.iface)?;
+        self.config.start_line = line;
+
+        Ok(())
+    }
+
+    /// Light every pixel regardless of display RAM contents, e.g. for a self test. This doesn't
+    /// alter the framebuffer or display RAM, so turning it off shows the previous contents again.
+    pub fn set_all_on(&mut self, on: bool) -> Result<(), DI::Error> {
+        Command::AllOn(on).send(&mut self.iface)
+    }
+
+    /// Set the multiplex ratio, i.e. the number of active display rows minus one. Use this for
+    /// panels that only connect a subset of the controller's rows. The value is clamped to the
+    /// valid range of 15 to 63.
+    pub fn set_multiplex(&mut self, ratio: u8) -> Result<(), DI::Error> {
+        let ratio = clamp_multiplex(ratio);
+
+        Command::Multiplex(ratio).send(&mut self.iface)?;
+        self.config.multiplex = Some(ratio);
+
+        Ok(())
+    }
+
+    /// Shift the display vertically by `offset` rows. Use this to center panels that don't start
+    /// at row 0 of the controller. The value is clamped to 63.
+    ///
+    /// Unlike [`set_start_line`](#method.set_start_line), which picks the RAM row shown first,
+    /// this shifts which COM row the first RAM row is driven on. With a reduced
+    /// [multiplex ratio](#method.set_multiplex) only that many rows are driven, so the offset
+    /// moves that band of rows up or down the panel, e.g. to center a 48 row image on a 64 row
+    /// controller.
+    pub fn set_display_offset(&mut self, offset: u8) -> Result<(), DI::Error> {
+        let offset = offset.min(63);
+
+        Command::DisplayOffset(offset).send(&mut self.iface)?;
+        self.config.display_offset = offset;
+
+        Ok(())
+    }
+
+    /// Start continuous hardware scrolling of pages `start_page` to `end_page` (inclusive) in
+    /// `direction`, moving one column every `interval`. Pages are clamped to the display height.
+    /// Writing display RAM, e.g. with `flush()`, stops the scroll.
+    pub fn scroll_horizontal(
+        &mut self,
+        direction: HScrollDir,
+        start_page: u8,
+        end_page: u8,
+        interval: ScrollInterval,
+    ) -> Result<(), DI::Error> {
         let (_, display_height) = self.display_size.dimensions();
+        let last_page = display_height / 8 - 1;
 
-        Command::DisplayOn(false).send(&mut self.iface)?;
-        Command::DisplayClockDiv(0x8, 0x0).send(&mut self.iface)?;
-        Command::Multiplex(display_height - 1).send(&mut self.iface)?;
-        Command::DisplayOffset(0).send(&mut self.iface)?;
-        Command::StartLine(0).send(&mut self.iface)?;
-        // TODO: Ability to turn charge pump on/off
-        Command::ChargePump(true).send(&mut self.iface)?;
-        Command::AddressMode(AddrMode::Horizontal).send(&mut self.iface)?;
-        Command::SegmentRemap(true).send(&mut self.iface)?;
-        Command::ReverseComDir(true).send(&mut self.iface)?;
-
-        match self.display_size {
-            DisplaySize::Display128x32 => Command::ComPinConfig(false, false).send(&mut self.iface),
-            DisplaySize::Display128x64 => Command::ComPinConfig(true, false).send(&mut self.iface),
-            DisplaySize::Display96x16 => Command::ComPinConfig(false, false).send(&mut self.iface),
-        }?;
-
-        Command::Contrast(0x8F).send(&mut self.iface)?;
-        Command::PreChargePeriod(0x1, 0xF).send(&mut self.iface)?;
-        Command::VcomhDeselect(VcomhLevel::Auto).send(&mut self.iface)?;
-        Command::AllOn(false).send(&mut self.iface)?;
-        Command::Invert(false).send(&mut self.iface)?;
+        let start_page = start_page.min(last_page);
+        let end_page = end_page.min(last_page).max(start_page);
+
+        // The scroll has to be stopped before it can be set up again
         Command::EnableScroll(false).send(&mut self.iface)?;
-        Command::DisplayOn(true).send(&mut self.iface)?;
+        Command::HScrollSetup(
+            direction,
+            (start_page * 8).into(),
+            (end_page * 8).into(),
+            interval,
+        )
+        .send(&mut self.iface)?;
+        Command::EnableScroll(true).send(&mut self.iface)?;
+        self.scrolling = true;
 
         Ok(())
     }
-}
 
-#[cfg(feature = "graphics")]
-use embedded_graphics::drawable;
-#[cfg(feature = "graphics")]
-use embedded_graphics::Drawing;
+    /// Stop hardware scrolling if it is active. The datasheet requires scrolling to be stopped
+    /// before display RAM is written, so `flush()` and the other methods that write display RAM
+    /// call this automatically. Scrolling must be started again afterwards.
+    ///
+    /// Scrolling shifts the display RAM contents, so stopping it marks the whole frame for
+    /// resending on the next `flush()`.
+    pub fn stop_scroll(&mut self) -> Result<(), DI::Error> {
+        if self.scrolling {
+            Command::EnableScroll(false).send(&mut self.iface)?;
+            self.scrolling = false;
+            self.dirty = true;
 
-#[cfg(feature = "graphics")]
-impl<DI> Drawing for SSD1306<DI>
-where
-    DI: DisplayInterface,
-{
-    fn draw<T>(&mut self, item_pixels: T)
-    where
-        T: Iterator<Item = drawable::Pixel>,
-    {
-        for (pos, color) in item_pixels {
-            self.set_pixel(pos.0, pos.1, color);
+            #[cfg(feature = "diff-flush")]
+            {
+                self.sent_valid = false;
+            }
         }
+
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    // TODO lol
+    /// Set the display RAM addressing mode used by [`write_data`](#method.write_data) and
+    /// [`write_area`](#method.write_area), e.g. vertical addressing to write an image column by
+    /// column. The framebuffer is always sent in horizontal mode, so `flush()` and the other
+    /// flush methods switch back to horizontal addressing first if another mode is set. In page
+    /// mode, the column and page windows set by `set_position()` and `write_area()` have no
+    /// effect.
+    pub fn set_addr_mode(&mut self, mode: AddrMode) -> Result<(), DI::Error> {
+        Command::AddressMode(mode).send(&mut self.iface)?;
+        self.addr_mode = mode;
+
+        Ok(())
+    }
+
+    /// Get the display RAM addressing mode last set
+    pub fn addr_mode(&self) -> AddrMode {
+        self.addr_mode
+    }
+
+    /// Get the display ready for a framebuffer flush: stop scrolling and make sure horizontal
+    /// addressing is used
+    fn prepare_flush(&mut self) -> Result<(), DI::Error> {
+        self.stop_scroll()?;
+
+        if !matches!(self.addr_mode, AddrMode::Horizontal) {
+            self.set_addr_mode(AddrMode::Horizontal)?;
+        }
+
+        Ok(())
+    }
+
+    /// Whether the display was last turned on
+    pub fn is_on(&self) -> bool {
+        self.display_on
+    }
+
+    /// Whether the display was last set to inverted
+    pub fn is_inverted(&self) -> bool {
+        self.config.inverted
+    }
+
+    /// Whether hardware scrolling was last enabled
+    pub fn is_scrolling(&self) -> bool {
+        self.scrolling
+    }
+
+    /// Whether the framebuffer has changed since the last flush, i.e. whether `flush()` would
+    /// send anything. A frame loop can simply draw and call `flush()` every iteration, as it's a
+    /// noop for unchanged frames.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Write out only the 8 pixel high pages `start_page` to `end_page` (inclusive) to the display.
+    /// `end_page` is clamped to the last page of the display, and nothing is sent if `start_page`
+    /// is past `end_page`. This doesn't reset the dirty state used by `flush()`.
+    pub fn flush_pages(&mut self, start_page: u8, end_page: u8) -> Result<(), DI::Error> {
+        let (display_width, display_height) = self.display_size.dimensions();
+
+        let end_page = end_page.min(display_height / 8 - 1);
+
+        if start_page > end_page {
+            return Ok(());
+        }
+
+        self.prepare_flush()?;
+
+        send_column_address(self.display_size, 0, display_width - 1, &mut self.iface)?;
+        Command::PageAddress((start_page * 8).into(), (end_page * 8).into())
+            .send(&mut self.iface)?;
+
+        let start = start_page as usize * display_width as usize;
+        let end = (end_page as usize + 1) * display_width as usize;
+
+        let buffer = self.buffer.as_ref();
+
+        self.iface.send_data(&buffer[start..end])?;
+
+        #[cfg(feature = "diff-flush")]
+        self.sent[start..end].copy_from_slice(&buffer[start..end]);
+
+        Ok(())
+    }
+
+    /// Send a typed command to the display. The driver doesn't track state changed this way, e.g.
+    /// `is_on()` or the settings restored by `init()`, so prefer the dedicated methods where they
+    /// exist.
+    #[cfg(feature = "unstable-commands")]
+    pub fn send_command(&mut self, cmd: Command) -> Result<(), DI::Error> {
+        cmd.send(&mut self.iface)
+    }
+
+    /// Set the column and page the next [`write_data`](#method.write_data) call will start at.
+    /// Values past the edge of the display are clamped to the last column or page.
+    pub fn set_position(&mut self, x: u8, page: u8) -> Result<(), DI::Error> {
+        let (display_width, display_height) = self.display_size.dimensions();
+
+        let x = x.min(display_width - 1);
+        let page = page.min(display_height / 8 - 1);
+
+        send_column_address(self.display_size, x, display_width - 1, &mut self.iface)?;
+        Command::PageAddress((page * 8).into(), (display_height - 1).into()).send(&mut self.iface)
+    }
+
+    /// Send raw bytes straight to display RAM at the position set by
+    /// [`set_position`](#method.set_position), bypassing the framebuffer. Each byte is a column of
+    /// 8 pixels. The next `flush()` won't overwrite this data unless the buffer has changed; use
+    /// `force_flush()` to restore the framebuffer contents.
+    pub fn write_data(&mut self, data: &[u8]) -> Result<(), DI::Error> {
+        self.stop_scroll()?;
+
+        #[cfg(feature = "diff-flush")]
+        {
+            self.sent_valid = false;
+        }
+
+        self.iface.send_data(data)
+    }
+
+    /// Write raw bytes straight to a window of display RAM, bypassing the framebuffer. The window
+    /// starts at `top_left` (column, page) and is `size` (columns, pages) big, clipped to the
+    /// display. Unlike [`set_position`](#method.set_position) and
+    /// [`write_data`](#method.write_data), no addressing state carries over from earlier calls.
+    pub fn write_area(
+        &mut self,
+        top_left: (u8, u8),
+        size: (u8, u8),
+        data: &[u8],
+    ) -> Result<(), DI::Error> {
+        let (display_width, display_height) = self.display_size.dimensions();
+        let (column, page) = top_left;
+        let (columns, pages) = size;
+
+        if columns == 0 || pages == 0 || column >= display_width || page >= display_height / 8 {
+            return Ok(());
+        }
+
+        let end_column = column.saturating_add(columns - 1).min(display_width - 1);
+        let end_page = page.saturating_add(pages - 1).min(display_height / 8 - 1);
+
+        send_column_address(self.display_size, column, end_column, &mut self.iface)?;
+        Command::PageAddress((page * 8).into(), (end_page * 8).into()).send(&mut self.iface)?;
+
+        self.write_data(data)
+    }
+
+    /// Replace the framebuffer with `data` and flush it to the display. `data` must be a full frame
+    /// in display RAM layout, i.e. `width * height / 8` bytes where each byte is a column of 8
+    /// pixels. Returns `Error::InvalidArgument` without touching the buffer if the length is wrong.
+    pub fn draw_full(&mut self, data: &[u8]) -> Result<(), Error<DI::Error>> {
+        let len = self.buffer_len();
+
+        if data.len() != len {
+            return Err(Error::InvalidArgument);
+        }
+
+        self.buffer.as_mut()[..len].copy_from_slice(data);
+        self.dirty = true;
+
+        self.flush().map_err(Error::Comm)?;
+
+        Ok(())
+    }
+
+    /// Turn a pixel on or off. A non-zero `value` is treated as on, `0` as off. If the X and Y
+    /// coordinates are out of the bounds of the display or outside the
+    /// [clip rectangle](#method.set_clip), this method call is a noop.
+    pub fn set_pixel(&mut self, x: u32, y: u32, value: u8) {
+        let (display_width, _) = self.display_size.dimensions();
+
+        if self.in_clip(x, y) && self.set_buffer_pixel(display_width as usize, x, y, value) {
+            self.dirty = true;
+        }
+    }
+
+    /// Set many pixels in one go, each given as `(x, y, on)`. This is the same as calling
+    /// [`set_pixel_bool`](#method.set_pixel_bool) for each of them, but only looks up the display
+    /// size and updates the dirty state once. Out of bounds and clipped pixels are ignored.
+    pub fn draw_pixels<I>(&mut self, pixels: I)
+    where
+        I: IntoIterator<Item = (u32, u32, bool)>,
+    {
+        let (display_width, _) = self.display_size.dimensions();
+        let mut changed = false;
+
+        for (x, y, on) in pixels {
+            if self.in_clip(x, y) {
+                changed |= self.set_buffer_pixel(display_width as usize, x, y, on as u8);
+            }
+        }
+
+        if changed {
+            self.dirty = true;
+        }
+    }
+
+    /// Like [`set_pixel`](#method.set_pixel), but returns whether the pixel actually changed. The
+    /// buffer is only marked dirty if it did, so redrawing an unchanged UI doesn't cause a flush.
+    pub fn set_pixel_changed(&mut self, x: u32, y: u32, value: u8) -> bool {
+        let (display_width, _) = self.display_size.dimensions();
+
+        if !self.in_clip(x, y) {
+            return false;
+        }
+
+        match self.pixel_location(display_width as usize, x, y) {
+            Some((idx, bit)) => {
+                let byte = &mut self.buffer.as_mut()[idx];
+                let old = *byte;
+
+                if value == 0 {
+                    *byte &= !bit;
+                } else {
+                    *byte |= bit;
+                }
+
+                let changed = *byte != old;
+                self.dirty |= changed;
+
+                changed
+            }
+            None => false,
+        }
+    }
+
+    /// Restrict pixel drawing to the rectangle at `top_left` of `size` (width, height) pixels, or
+    /// lift the restriction with `None`. While set, [`set_pixel`](#method.set_pixel) and the
+    /// embedded-graphics drawing impls, including `DrawTarget::clear()`, silently drop pixels
+    /// outside the rectangle, so widgets can't draw over their neighbours. `invert_region()` and
+    /// `clear_region()` only touch the part of their rectangle inside it. Coordinates are drawing
+    /// coordinates, taking rotation into account. Byte based methods such as `blit()`,
+    /// `draw_full()`, `fill_pattern()` and `clear_buffer()` aren't clipped.
+    pub fn set_clip(&mut self, clip: Option<((u32, u32), (u32, u32))>) {
+        self.clip = clip.map(|(top_left, size)| {
+            (
+                top_left.0,
+                top_left.1,
+                top_left.0.saturating_add(size.0),
+                top_left.1.saturating_add(size.1),
+            )
+        });
+    }
+
+    /// Turn a pixel on (`true`) or off (`false`). Like [`set_pixel`](#method.set_pixel), out of
+    /// bounds coordinates are ignored.
+    pub fn set_pixel_bool(&mut self, x: u32, y: u32, on: bool) {
+        self.set_pixel(x, y, on as u8);
+    }
+
+    /// Write the 8 vertical pixels of column `x` in `page` in one go: bits set in `mask` are set
+    /// to the corresponding bit in `bits`, the least significant bit being the top pixel. Other
+    /// bits are left alone. This works on the display RAM layout and ignores rotation. Out of
+    /// bounds columns and pages are ignored.
+    pub fn set_column_bits(&mut self, x: u32, page: u8, bits: u8, mask: u8) {
+        let (display_width, display_height) = self.display_size.dimensions();
+
+        if x >= u32::from(display_width) || page >= display_height / 8 {
+            return;
+        }
+
+        let byte = &mut self.buffer.as_mut()[page as usize * display_width as usize + x as usize];
+
+        *byte = (*byte & !mask) | (bits & mask);
+        self.dirty = true;
+    }
+
+    /// Fill columns `x0` to `x1` (inclusive) of `page` with `pattern`, e.g. for a progress bar.
+    /// Each column gets the same byte, the least significant bit being the top pixel. Like
+    /// [`set_column_bits`](#method.set_column_bits) this works on the display RAM layout and
+    /// ignores rotation. `x1` is clamped to the last column, and nothing happens if `page` is out
+    /// of bounds or `x0` is past `x1`.
+    pub fn draw_hline_page(&mut self, page: u8, x0: u8, x1: u8, pattern: u8) {
+        let (display_width, display_height) = self.display_size.dimensions();
+
+        let x1 = x1.min(display_width - 1);
+
+        if page >= display_height / 8 || x0 > x1 {
+            return;
+        }
+
+        let row = page as usize * display_width as usize;
+
+        for byte in &mut self.buffer.as_mut()[row + x0 as usize..=row + x1 as usize] {
+            *byte = pattern;
+        }
+
+        self.dirty = true;
+    }
+
+    /// OR a `width` by `height` pixel image into the framebuffer with its top left corner at
+    /// `top_left`. `data` is in display RAM layout: each byte is a column of 8 pixels with the
+    /// least significant bit at the top, `width` bytes per 8 pixel high row. `top_left` doesn't
+    /// need to be page aligned. The image is clipped to the display, and missing data is treated
+    /// as blank.
+    pub fn blit(&mut self, top_left: (u32, u32), width: u32, height: u32, data: &[u8]) {
+        let (display_width, display_height) = self.display_size.dimensions();
+        let (logical_width, logical_height) = self.dimensions();
+        let (x0, y0) = top_left;
+
+        if width == 0 {
+            return;
+        }
+
+        for (row, bytes) in data.chunks(width as usize).enumerate() {
+            let row_top = (row as u32).saturating_mul(8);
+            let y = y0.saturating_add(row_top);
+
+            if row_top >= height || y >= u32::from(logical_height) {
+                break;
+            }
+
+            // Clear bits below the bottom of the image on its last row
+            let rows_left = height - row_top;
+            let row_mask = if rows_left < 8 {
+                (1u8 << rows_left) - 1
+            } else {
+                0xFF
+            };
+
+            for (col, byte) in bytes.iter().enumerate() {
+                let x = x0.saturating_add(col as u32);
+                let byte = byte & row_mask;
+
+                if x >= u32::from(logical_width) {
+                    break;
+                }
+
+                match self.config.rotation {
+                    DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => {
+                        self.blit_byte(display_width, display_height, x, y, byte);
+                    }
+                    // Columns of the image aren't columns in display RAM, so go pixel by pixel
+                    DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => {
+                        for bit in 0..8 {
+                            let y = y.saturating_add(bit);
+
+                            if y < u32::from(logical_height) && byte & (1 << bit) != 0 {
+                                self.set_buffer_pixel(display_width as usize, x, y, 1);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        self.dirty = true;
+    }
+
+    /// OR a column byte into the framebuffer at pixel `y`, splitting it across two pages if `y`
+    /// isn't page aligned
+    fn blit_byte(&mut self, display_width: u8, display_height: u8, x: u32, y: u32, byte: u8) {
+        let page = (y / 8) as usize;
+        let shift = y % 8;
+        let idx = page * display_width as usize + x as usize;
+        let buffer = self.buffer.as_mut();
+
+        buffer[idx] |= byte << shift;
+
+        if shift != 0 && page + 1 < display_height as usize / 8 {
+            buffer[idx + display_width as usize] |= byte >> (8 - shift);
+        }
+    }
+
+    /// Shift the framebuffer contents up by `pixels` rows and clear the rows exposed at the bottom,
+    /// e.g. to make room for a new line in a log view. Unlike hardware scrolling or
+    /// [`set_start_line`](#method.set_start_line) this changes the framebuffer itself. It works
+    /// on the display RAM layout and ignores rotation. You need to call `flush()` for any effect
+    /// on the screen.
+    pub fn scroll_up(&mut self, pixels: u32) {
+        let (display_width, display_height) = self.display_size.dimensions();
+        let width = display_width as usize;
+        let pages = display_height as usize / 8;
+
+        let page_shift = (pixels / 8).min(pages as u32) as usize;
+        let bit_shift = pixels % 8;
+
+        let buffer = self.buffer.as_mut();
+
+        // Rows only ever move up, so going top to bottom never reads a page that's already been
+        // overwritten
+        for page in 0..pages {
+            let src = page + page_shift;
+
+            for x in 0..width {
+                let lo = if src < pages {
+                    buffer[src * width + x]
+                } else {
+                    0
+                };
+                let hi = if src + 1 < pages {
+                    buffer[(src + 1) * width + x]
+                } else {
+                    0
+                };
+
+                buffer[page * width + x] = if bit_shift == 0 {
+                    lo
+                } else {
+                    (lo >> bit_shift) | (hi << (8 - bit_shift))
+                };
+            }
+        }
+
+        self.dirty = true;
+    }
+
+    /// Invert the pixels in the rectangle at `top_left` of `size` (width, height) pixels, e.g. to
+    /// highlight a menu entry. The rectangle is clipped to the display and the clip rectangle.
+    /// Inverting the same region again restores it. You need to call `flush()` for any effect on the screen.
+    pub fn invert_region(&mut self, top_left: (u32, u32), size: (u32, u32)) {
+        self.update_region(top_left, size, |byte, bit| *byte ^= bit);
+    }
+
+    /// Turn off the pixels in the rectangle at `top_left` of `size` (width, height) pixels,
+    /// leaving the rest of the buffer untouched. The rectangle is clipped to the display and the
+    /// clip rectangle. You need
+    /// to call `flush()` for any effect on the screen.
+    pub fn clear_region(&mut self, top_left: (u32, u32), size: (u32, u32)) {
+        self.update_region(top_left, size, |byte, bit| *byte &= !bit);
+    }
+
+    /// Call `update` with the framebuffer byte and bit mask of every pixel in a rectangle, clipped
+    /// to the display and the clip rectangle
+    fn update_region<F>(&mut self, top_left: (u32, u32), size: (u32, u32), mut update: F)
+    where
+        F: FnMut(&mut u8, u8),
+    {
+        let (display_width, _) = self.display_size.dimensions();
+        let (width, height) = self.dimensions();
+
+        let (clip_x0, clip_y0, clip_x1, clip_y1) = self.clip.unwrap_or((0, 0, u32::MAX, u32::MAX));
+
+        let x_end = top_left.0.saturating_add(size.0).min(u32::from(width));
+        let y_end = top_left.1.saturating_add(size.1).min(u32::from(height));
+        let (x_start, x_end) = (top_left.0.max(clip_x0), x_end.min(clip_x1));
+        let (y_start, y_end) = (top_left.1.max(clip_y0), y_end.min(clip_y1));
+
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                if let Some((idx, bit)) = self.pixel_location(display_width as usize, x, y) {
+                    update(&mut self.buffer.as_mut()[idx], bit);
+                    self.dirty = true;
+                }
+            }
+        }
+    }
+
+    /// Number of framebuffer bytes used by the display
+    fn buffer_len(&self) -> usize {
+        let (display_width, display_height) = self.display_size.dimensions();
+        let len = display_width as usize * display_height as usize / 8;
+
+        // Guaranteed by the constructors
+        debug_assert!(self.buffer.as_ref().len() >= len);
+
+        len
+    }
+
+    /// Whether a pixel is inside the clip rectangle, if one is set
+    #[inline]
+    fn in_clip(&self, x: u32, y: u32) -> bool {
+        match self.clip {
+            Some((x0, y0, x1, y1)) => x >= x0 && y >= y0 && x < x1 && y < y1,
+            None => true,
+        }
+    }
+
+    /// Set a pixel in the framebuffer without touching the dirty flag, so that batched draws only
+    /// look up the display width and update the flag once. Returns whether the pixel was in bounds.
+    #[inline]
+    fn set_buffer_pixel(&mut self, display_width: usize, x: u32, y: u32, value: u8) -> bool {
+        match self.pixel_location(display_width, x, y) {
+            Some((idx, bit)) => {
+                let byte = &mut self.buffer.as_mut()[idx];
+
+                if value == 0 {
+                    *byte &= !bit;
+                } else {
+                    *byte |= bit;
+                }
+
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Get the framebuffer byte index and bit mask for a pixel, or `None` if it's out of bounds
+    #[inline]
+    fn pixel_location(&self, display_width: usize, x: u32, y: u32) -> Option<(usize, u8)> {
+        // Rows and columns are swapped in display RAM for 90 and 270 degree rotations; the rest of
+        // the rotation is handled by the segment remap and COM scan direction set in `init()`
+        let (x, y) = match self.config.rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => (x, y),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => (y, x),
+        };
+
+        if x as usize >= display_width || y >= u32::from(self.display_size.height()) {
+            return None;
+        }
+
+        let idx = ((y as usize) / 8 * display_width) + (x as usize);
+
+        Some((idx, 1 << (y % 8)))
+    }
+
+    // Display is set up in column mode, i.e. a byte walks down a column of 8 pixels from column 0 on the left, to column _n_ on the right
+    /// Initialize display in column mode.
+    pub fn init(&mut self) -> Result<(), DI::Error> {
+        let (_, display_height) = self.display_size.dimensions();
+
+        // Everything up to the extra commands goes out in one batch, which saves a transaction
+        // per command on I2C
+        let mut batch = CommandBatch::new(&mut self.iface);
+
+        Command::DisplayOn(false).send(&mut batch)?;
+        self.display_on = false;
+
+        // Display RAM contents are undefined after a reset, so the next flush sends everything
+        self.dirty = true;
+
+        #[cfg(feature = "diff-flush")]
+        {
+            self.sent_valid = false;
+        }
+
+        Command::DisplayClockDiv(self.config.osc_freq, self.config.clock_divide)
+            .send(&mut batch)?;
+        Command::Multiplex(self.config.multiplex.unwrap_or(display_height - 1)).send(&mut batch)?;
+        Command::DisplayOffset(self.config.display_offset).send(&mut batch)?;
+        Command::StartLine(self.config.start_line).send(&mut batch)?;
+        Command::ChargePump(self.config.charge_pump).send(&mut batch)?;
+        Command::AddressMode(AddrMode::Horizontal).send(&mut batch)?;
+        self.addr_mode = AddrMode::Horizontal;
+        send_rotation(&self.config, &mut batch)?;
+
+        let (alternative, left_right_remap) = match self.config.com_pins {
+            Some(com_pins) => com_pins,
+            None => match self.display_size {
+                DisplaySize::Display128x32 => (false, false),
+                DisplaySize::Display128x64 => (true, false),
+                DisplaySize::Display96x16 => (false, false),
+                DisplaySize::Display64x32 => (true, false),
+            },
+        };
+
+        Command::ComPinConfig(alternative, left_right_remap).send(&mut batch)?;
+
+        Command::Contrast(self.config.contrast).send(&mut batch)?;
+        let (phase1, phase2) = self.config.precharge;
+        Command::PreChargePeriod(phase1, phase2).send(&mut batch)?;
+        Command::VcomhDeselect(self.config.vcomh).send(&mut batch)?;
+        Command::AllOn(false).send(&mut batch)?;
+        Command::Invert(self.config.inverted).send(&mut batch)?;
+        // Scrolling needs its setup sent again after init, so always start with it stopped
+        Command::EnableScroll(false).send(&mut batch)?;
+        self.scrolling = false;
+
+        batch.finish()?;
+
+        if !self.config.extra_init.is_empty() {
+            self.iface.send_commands(self.config.extra_init)?;
+        }
+
+        Command::DisplayOn(true).send(&mut self.iface)?;
+        self.display_on = true;
+
+        Ok(())
+    }
+}
+
+/// Send a column window, shifted to where the panel of `display_size` starts in controller RAM
+fn send_column_address<DI>(
+    display_size: DisplaySize,
+    start: u8,
+    end: u8,
+    iface: &mut DI,
+) -> Result<(), DI::Error>
+where
+    DI: DisplayInterface,
+{
+    let offset = display_size.column_offset();
+
+    Command::ColumnAddress(offset + start, offset + end).send(iface)
+}
+
+/// Send the segment remap and COM scan direction for the configured rotation, unless overridden
+fn send_rotation<DI>(config: &DisplayConfig, iface: &mut DI) -> Result<(), DI::Error>
+where
+    DI: DisplayInterface,
+{
+    let (remap, reverse_com) = match config.rotation {
+        DisplayRotation::Rotate0 => (true, true),
+        DisplayRotation::Rotate90 => (false, true),
+        DisplayRotation::Rotate180 => (false, false),
+        DisplayRotation::Rotate270 => (true, false),
+    };
+
+    Command::SegmentRemap(config.segment_remap.unwrap_or(remap)).send(iface)?;
+    Command::ReverseComDir(config.reverse_com_dir.unwrap_or(reverse_com)).send(iface)
+}
+
+#[cfg(feature = "graphics")]
+use embedded_graphics::drawable;
+#[cfg(feature = "graphics")]
+use embedded_graphics::Drawing;
+
+#[cfg(feature = "graphics")]
+impl<DI, BUF> Drawing for SSD1306<DI, BUF>
+where
+    DI: DisplayInterface,
+    BUF: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn draw<T>(&mut self, item_pixels: T)
+    where
+        T: Iterator<Item = drawable::Pixel>,
+    {
+        let (display_width, _) = self.display_size.dimensions();
+        let mut changed = false;
+
+        for (pos, color) in item_pixels {
+            if self.in_clip(pos.0, pos.1) {
+                changed |= self.set_buffer_pixel(display_width as usize, pos.0, pos.1, color);
+            }
+        }
+
+        if changed {
+            self.dirty = true;
+        }
+    }
+}
+
+#[cfg(feature = "graphics")]
+impl<DI, BUF> SSD1306<DI, BUF>
+where
+    DI: DisplayInterface,
+    BUF: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Like [`Drawing::draw`], but stops at the first pixel that can't be drawn and returns
+    /// `Error::InvalidArgument` instead of skipping it. Pixels drawn before that stay in the
+    /// framebuffer, so a failed call leaves a partial draw behind. Pixels outside the
+    /// [clip rectangle](#method.set_clip) are dropped as usual and aren't an error.
+    pub fn try_draw<T>(&mut self, item_pixels: T) -> Result<(), Error<DI::Error>>
+    where
+        T: Iterator<Item = drawable::Pixel>,
+    {
+        let (display_width, _) = self.display_size.dimensions();
+
+        for (pos, color) in item_pixels {
+            if !self.in_clip(pos.0, pos.1) {
+                continue;
+            }
+
+            if !self.set_buffer_pixel(display_width as usize, pos.0, pos.1, color) {
+                return Err(Error::InvalidArgument);
+            }
+
+            self.dirty = true;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "graphics-core")]
+use core::convert::Infallible;
+#[cfg(feature = "graphics-core")]
+use embedded_graphics_core::draw_target::DrawTarget;
+#[cfg(feature = "graphics-core")]
+use embedded_graphics_core::geometry::{OriginDimensions, Size};
+#[cfg(feature = "graphics-core")]
+use embedded_graphics_core::pixelcolor::BinaryColor;
+#[cfg(feature = "graphics-core")]
+use embedded_graphics_core::primitives::Rectangle;
+#[cfg(feature = "graphics-core")]
+use embedded_graphics_core::Pixel;
+
+#[cfg(feature = "graphics-core")]
+impl<DI, BUF> DrawTarget for SSD1306<DI, BUF>
+where
+    DI: DisplayInterface,
+    BUF: AsRef<[u8]> + AsMut<[u8]>,
+{
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item = Pixel<Self::Color>>,
+    {
+        let (display_width, _) = self.display_size.dimensions();
+        let (width, height) = self.dimensions();
+        let mut changed = false;
+
+        for Pixel(point, color) in pixels {
+            if point.x >= 0
+                && point.y >= 0
+                && point.x < i32::from(width)
+                && point.y < i32::from(height)
+                && self.in_clip(point.x as u32, point.y as u32)
+            {
+                changed |= self.set_buffer_pixel(
+                    display_width as usize,
+                    point.x as u32,
+                    point.y as u32,
+                    color.is_on() as u8,
+                );
+            }
+        }
+
+        if changed {
+            self.dirty = true;
+        }
+
+        Ok(())
+    }
+
+    /// Fill the whole display with `color` in one go rather than pixel by pixel. With a clip
+    /// rectangle set, only the pixels inside it are filled.
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        if self.clip.is_some() {
+            let (width, height) = self.dimensions();
+
+            self.update_region((0, 0), (width.into(), height.into()), |byte, bit| {
+                if color.is_on() {
+                    *byte |= bit;
+                } else {
+                    *byte &= !bit;
+                }
+            });
+
+            return Ok(());
+        }
+
+        let len = self.buffer_len();
+        let fill = if color.is_on() { 0xFF } else { 0x00 };
+
+        for byte in &mut self.buffer.as_mut()[..len] {
+            *byte = fill;
+        }
+
+        self.dirty = true;
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "graphics-core")]
+impl<DI, BUF> OriginDimensions for SSD1306<DI, BUF>
+where
+    DI: DisplayInterface,
+    BUF: AsRef<[u8]> + AsMut<[u8]>,
+{
+    fn size(&self) -> Size {
+        let (width, height) = self.dimensions();
+
+        Size::new(u32::from(width), u32::from(height))
+    }
+}
+
+#[cfg(feature = "graphics-core")]
+impl<DI, BUF> SSD1306<DI, BUF>
+where
+    DI: DisplayInterface,
+    BUF: AsRef<[u8]> + AsMut<[u8]>,
+{
+    /// Write out only the part of the framebuffer covered by `bb`, e.g. the combined bounding box
+    /// of everything drawn since the last flush. `bb` is in drawing coordinates and is clipped to
+    /// the display, then rounded out to whole 8 pixel pages. Like
+    /// [`flush_pages`](#method.flush_pages), this doesn't reset the dirty state used by `flush()`.
+    pub fn flush_bounding_box(&mut self, bb: Rectangle) -> Result<(), DI::Error> {
+        let (display_width, _) = self.display_size.dimensions();
+        let (width, height) = self.dimensions();
+
+        let x0 = bb.top_left.x.max(0);
+        let y0 = bb.top_left.y.max(0);
+        let x1 = bb
+            .top_left
+            .x
+            .saturating_add(bb.size.width.min(i32::MAX as u32) as i32)
+            .min(i32::from(width));
+        let y1 = bb
+            .top_left
+            .y
+            .saturating_add(bb.size.height.min(i32::MAX as u32) as i32)
+            .min(i32::from(height));
+
+        if x0 >= x1 || y0 >= y1 {
+            return Ok(());
+        }
+
+        // Display RAM rows and columns are swapped for 90 and 270 degree rotations
+        let (column_range, row_range) = match self.config.rotation {
+            DisplayRotation::Rotate0 | DisplayRotation::Rotate180 => ((x0, x1), (y0, y1)),
+            DisplayRotation::Rotate90 | DisplayRotation::Rotate270 => ((y0, y1), (x0, x1)),
+        };
+
+        let start_column = column_range.0 as u8;
+        let end_column = (column_range.1 - 1) as u8;
+        let start_page = (row_range.0 / 8) as u8;
+        let end_page = ((row_range.1 - 1) / 8) as u8;
+
+        self.prepare_flush()?;
+
+        send_column_address(self.display_size, start_column, end_column, &mut self.iface)?;
+        Command::PageAddress((start_page * 8).into(), (end_page * 8).into())
+            .send(&mut self.iface)?;
+
+        for page in start_page..=end_page {
+            let start = page as usize * display_width as usize + start_column as usize;
+            let end = page as usize * display_width as usize + end_column as usize + 1;
+
+            let buffer = self.buffer.as_ref();
+
+            self.iface.send_data(&buffer[start..end])?;
+
+            #[cfg(feature = "diff-flush")]
+            self.sent[start..end].copy_from_slice(&buffer[start..end]);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::Infallible;
+    use std::vec::Vec;
+
+    use super::*;
+    use interface::InterfaceError;
+
+    /// A transfer recorded by `MockInterface`
+    #[derive(Debug, PartialEq)]
+    enum Sent {
+        Command(u8),
+        Data(Vec<u8>),
+    }
+
+    /// Display interface that records everything sent through it
+    struct MockInterface {
+        sent: Vec<Sent>,
+    }
+
+    impl MockInterface {
+        fn new() -> Self {
+            Self { sent: Vec::new() }
+        }
+
+        /// All command bytes sent, in order
+        fn commands(&self) -> Vec<u8> {
+            self.sent
+                .iter()
+                .filter_map(|sent| match *sent {
+                    Sent::Command(cmd) => Some(cmd),
+                    Sent::Data(_) => None,
+                })
+                .collect()
+        }
+
+        /// Number of data bytes sent
+        fn data_len(&self) -> usize {
+            self.sent
+                .iter()
+                .map(|sent| match *sent {
+                    Sent::Command(_) => 0,
+                    Sent::Data(ref data) => data.len(),
+                })
+                .sum()
+        }
+    }
+
+    impl DisplayInterface for MockInterface {
+        type Error = Infallible;
+
+        fn send_command(&mut self, cmd: u8) -> Result<(), Infallible> {
+            self.sent.push(Sent::Command(cmd));
+
+            Ok(())
+        }
+
+        fn send_data(&mut self, buf: &[u8]) -> Result<(), Infallible> {
+            self.sent.push(Sent::Data(buf.to_vec()));
+
+            Ok(())
+        }
+    }
+
+    /// I2C or SPI bus that records every write
+    struct MockBus<W> {
+        writes: Vec<Vec<W>>,
+    }
+
+    impl<W> MockBus<W> {
+        fn new() -> Self {
+            Self { writes: Vec::new() }
+        }
+
+        /// Length of the longest write
+        fn longest_write(&self) -> usize {
+            self.writes
+                .iter()
+                .map(|write| write.len())
+                .max()
+                .unwrap_or(0)
+        }
+    }
+
+    impl<W> hal::spi::ErrorType for MockBus<W> {
+        type Error = Infallible;
+    }
+
+    impl<W: Copy + 'static> hal::spi::SpiDevice<W> for MockBus<W> {
+        fn transaction(
+            &mut self,
+            operations: &mut [hal::spi::Operation<'_, W>],
+        ) -> Result<(), Infallible> {
+            for operation in operations {
+                if let hal::spi::Operation::Write(words) = *operation {
+                    self.writes.push(words.to_vec());
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    impl hal::i2c::ErrorType for MockBus<u8> {
+        type Error = Infallible;
+    }
+
+    impl hal::i2c::I2c for MockBus<u8> {
+        fn transaction(
+            &mut self,
+            _address: u8,
+            operations: &mut [hal::i2c::Operation<'_>],
+        ) -> Result<(), Infallible> {
+            for operation in operations {
+                if let hal::i2c::Operation::Write(bytes) = *operation {
+                    self.writes.push(bytes.to_vec());
+                }
+            }
+
+            Ok(())
+        }
+    }
+
+    /// D/C pin that can't fail
+    struct MockPin;
+
+    impl hal::digital::ErrorType for MockPin {
+        type Error = Infallible;
+    }
+
+    impl OutputPin for MockPin {
+        fn set_low(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+
+        fn set_high(&mut self) -> Result<(), Infallible> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn spi_transaction_limit_splits_writes() {
+        let mut disp = Builder::new()
+            .with_max_transaction_bytes(16)
+            .connect_spi(MockBus::new(), MockPin);
+
+        disp.init().unwrap();
+        assert_eq!(disp.flush().unwrap(), 1024);
+
+        let (spi, _) = disp.release().0.release();
+
+        assert_eq!(spi.longest_write(), 16);
+        assert!(spi.writes.len() >= 1024 / 16);
+    }
+
+    #[test]
+    fn spi_transaction_limit_of_zero_fails() {
+        let mut disp = Builder::new()
+            .with_max_transaction_bytes(0)
+            .connect_spi(MockBus::new(), MockPin);
+
+        assert!(matches!(
+            disp.init(),
+            Err(InterfaceError::TransactionTooLong)
+        ));
+    }
+
+    #[test]
+    fn spi_3wire_transaction_limit_splits_writes() {
+        let mut disp = Builder::new()
+            .with_max_transaction_bytes(4)
+            .connect_spi_3wire(MockBus::new());
+
+        disp.init().unwrap();
+        assert_eq!(disp.flush().unwrap(), 1024);
+
+        let spi = disp.release().0.release();
+        let last = spi.writes.last().unwrap();
+
+        assert_eq!(spi.longest_write(), 4);
+        // Data words have the D/C bit set
+        assert!(last.iter().all(|word| word & 0x100 != 0));
+    }
+
+    #[test]
+    fn i2c_transaction_limit_splits_writes() {
+        let mut disp = Builder::new()
+            .with_max_transaction_bytes(8)
+            .connect_i2c(MockBus::new());
+
+        disp.init().unwrap();
+        assert_eq!(disp.flush().unwrap(), 1024);
+
+        let i2c = disp.release().0.release();
+        let data: Vec<_> = i2c.writes.iter().filter(|write| write[0] == 0x40).collect();
+
+        assert_eq!(i2c.longest_write(), 8);
+        // Each data transaction is a control byte followed by 7 data bytes, the last one shorter
+        assert_eq!(data.len(), 147);
+    }
+
+    #[test]
+    fn flush_after_init_sends_whole_frame() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.flush().unwrap();
+        disp.init().unwrap();
+        disp.iface.sent.clear();
+
+        assert_eq!(disp.flush().unwrap(), 1024);
+        assert_eq!(disp.iface.data_len(), 1024);
+    }
+
+    #[test]
+    fn clean_flush_sends_nothing() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.flush().unwrap();
+        disp.iface.sent.clear();
+
+        assert_eq!(disp.flush().unwrap(), 0);
+        assert!(disp.iface.sent.is_empty());
+    }
+
+    #[test]
+    fn flush_stops_scroll_before_writing() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.flush().unwrap();
+        disp.scroll_horizontal(HScrollDir::LeftToRight, 0, 7, ScrollInterval::Frames2)
+            .unwrap();
+        disp.iface.sent.clear();
+
+        // The buffer is clean, but the scroll has moved the display RAM contents
+        assert_eq!(disp.flush().unwrap(), 1024);
+        assert!(!disp.is_scrolling());
+
+        // EnableScroll(false) goes out first, before any of the data
+        assert_eq!(disp.iface.sent[0], Sent::Command(0x2E));
+        assert_eq!(disp.iface.data_len(), 1024);
+    }
+
+    #[test]
+    fn init_sends_configured_commands() {
+        let config = DisplayConfig {
+            rotation: DisplayRotation::Rotate180,
+            clock_divide: 0x1,
+            osc_freq: 0xF,
+            contrast: 0x40,
+            precharge: (0x2, 0x2),
+            vcomh: VcomhLevel::V083,
+            inverted: true,
+            charge_pump: false,
+            display_offset: 4,
+            start_line: 8,
+            extra_init: &[0xE3],
+            ..DisplayConfig::default()
+        };
+        let mut disp =
+            SSD1306::with_config(MockInterface::new(), DisplaySize::Display128x32, config);
+
+        disp.init().unwrap();
+
+        #[rustfmt::skip]
+        let expected = [
+            0xAE,       // Display off
+            0xD5, 0xF1, // Clock divide 1, oscillator frequency 15
+            0xA8, 0x1F, // Multiplex ratio from the display height
+            0xD3, 0x04, // Display offset
+            0x48,       // Start line
+            0x8D, 0x10, // Charge pump off
+            0x20, 0x00, // Horizontal addressing
+            0xA0,       // No segment remap for 180 degrees
+            0xC0,       // Normal COM scan direction for 180 degrees
+            0xDA, 0x02, // Sequential COM pins for 128x32
+            0x81, 0x40, // Contrast
+            0xD9, 0x22, // Pre-charge periods
+            0xDB, 0x30, // VCOMH deselect level
+            0xA4,       // Follow display RAM
+            0xA7,       // Inverted
+            0x2E,       // Scrolling stopped
+            0xE3,       // Extra init command
+            0xAF,       // Display on
+        ];
+
+        assert_eq!(disp.iface.commands(), &expected[..]);
+    }
+
+    #[test]
+    fn blit_page_aligned() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.blit((2, 8), 2, 8, &[0xFF, 0x81]);
+
+        let buffer = disp.buffer();
+
+        assert_eq!(&buffer[128 + 2..128 + 4], &[0xFF, 0x81]);
+        assert_eq!(buffer.iter().filter(|byte| **byte != 0).count(), 2);
+    }
+
+    #[test]
+    fn blit_unaligned_splits_across_pages() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.blit((3, 4), 2, 8, &[0xFF, 0x81]);
+
+        let buffer = disp.buffer();
+
+        assert_eq!(&buffer[3..5], &[0xF0, 0x10]);
+        assert_eq!(&buffer[128 + 3..128 + 5], &[0x0F, 0x08]);
+        assert_eq!(buffer.iter().filter(|byte| **byte != 0).count(), 4);
+    }
+
+    #[test]
+    fn blit_masks_rows_below_height() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.blit((0, 4), 1, 5, &[0xFF]);
+
+        assert_eq!(disp.buffer()[0], 0xF0);
+        assert_eq!(disp.buffer()[128], 0x01);
+    }
+
+    #[test]
+    fn blit_far_off_screen_does_nothing() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.blit((u32::MAX, u32::MAX), 2, 16, &[0xFF; 4]);
+
+        assert!(disp.buffer().iter().all(|byte| *byte == 0));
+    }
+
+    /// Driver with pixels set at the top of pages 0 and 1 and all of page 7 in column 0
+    fn scroll_test_display() -> SSD1306<MockInterface> {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.buffer_mut()[0] = 0x01;
+        disp.buffer_mut()[128] = 0x01;
+        disp.buffer_mut()[7 * 128] = 0xFF;
+
+        disp
+    }
+
+    #[test]
+    fn scroll_up_by_one_pixel() {
+        let mut disp = scroll_test_display();
+
+        disp.scroll_up(1);
+
+        let buffer = disp.buffer();
+
+        // The top row is dropped and page 1's top row moves to the bottom of page 0
+        assert_eq!(buffer[0], 0x80);
+        assert_eq!(buffer[128], 0x00);
+        assert_eq!(buffer[6 * 128], 0x80);
+        assert_eq!(buffer[7 * 128], 0x7F);
+    }
+
+    #[test]
+    fn scroll_up_by_one_page() {
+        let mut disp = scroll_test_display();
+
+        disp.scroll_up(8);
+
+        let buffer = disp.buffer();
+
+        assert_eq!(buffer[0], 0x01);
+        assert_eq!(buffer[128], 0x00);
+        assert_eq!(buffer[6 * 128], 0xFF);
+        assert_eq!(buffer[7 * 128], 0x00);
+    }
+
+    #[test]
+    fn flush_chunks_yield_commands_and_data() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x32);
+        let mut iface = MockInterface::new();
+
+        disp.set_pixel(0, 8, 1);
+        disp.scroll_horizontal(HScrollDir::LeftToRight, 0, 3, ScrollInterval::Frames2)
+            .unwrap();
+        disp.iface.sent.clear();
+
+        {
+            let chunks: Vec<_> = disp.flush_chunks().collect();
+
+            assert_eq!(chunks.len(), 4);
+            // Scrolling is stopped before the first window is set up
+            assert_eq!(chunks[0].commands(), &[0x2E, 0x21, 0, 127, 0x22, 0, 0]);
+            assert_eq!(chunks[1].commands(), &[0x21, 0, 127, 0x22, 1, 1]);
+            assert_eq!(chunks[1].data().len(), 128);
+            assert_eq!(chunks[1].data()[0], 0x01);
+
+            for chunk in &chunks {
+                chunk.send(&mut iface).unwrap();
+            }
+        }
+
+        // The caller sends the chunks, not the driver
+        assert!(disp.iface.sent.is_empty());
+        assert_eq!(iface.data_len(), 512);
+        assert!(!disp.is_dirty());
+        assert!(!disp.is_scrolling());
+    }
+
+    #[test]
+    fn flush_data_length_per_size() {
+        let sizes = [
+            (DisplaySize::Display128x64, 1024),
+            (DisplaySize::Display128x32, 512),
+            (DisplaySize::Display96x16, 192),
+            (DisplaySize::Display64x32, 256),
+        ];
+
+        for &(size, len) in &sizes {
+            let mut disp = SSD1306::new(MockInterface::new(), size);
+
+            assert_eq!(disp.buffer().len(), len);
+            assert_eq!(disp.flush().unwrap(), len);
+            assert_eq!(disp.iface.data_len(), len);
+        }
+    }
+
+    #[test]
+    fn flush_64x32_uses_column_offset() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display64x32);
+
+        assert_eq!(disp.flush().unwrap(), 256);
+        assert_eq!(disp.iface.data_len(), 256);
+
+        // The panel sits in the middle of the 128 column controller RAM
+        assert_eq!(disp.iface.commands(), &[0x21, 32, 95, 0x22, 0, 3]);
+    }
+
+    #[test]
+    fn flush_96x16_sends_two_pages() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display96x16);
+
+        assert_eq!(disp.flush().unwrap(), 192);
+        assert_eq!(disp.iface.data_len(), 192);
+        assert_eq!(disp.iface.commands(), &[0x21, 0, 95, 0x22, 0, 1]);
+    }
+
+    #[test]
+    fn clear_region_leaves_surroundings() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        for byte in disp.buffer_mut() {
+            *byte = 0xFF;
+        }
+
+        disp.clear_region((8, 4), (16, 16));
+
+        let buffer = disp.buffer();
+
+        for y in 0..64 {
+            for x in 0..128 {
+                let on = buffer[y / 8 * 128 + x] & (1 << (y % 8)) != 0;
+                let inside = (8..24).contains(&x) && (4..20).contains(&y);
+
+                assert_eq!(on, !inside, "pixel ({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn draw_hline_page_writes_inclusive_range() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.draw_hline_page(2, 10, 20, 0xAA);
+
+        let buffer = disp.buffer();
+
+        assert!(buffer[256 + 10..=256 + 20].iter().all(|byte| *byte == 0xAA));
+        assert_eq!(buffer[256 + 9], 0);
+        assert_eq!(buffer[256 + 21], 0);
+        assert_eq!(buffer.iter().filter(|byte| **byte != 0).count(), 11);
+    }
+
+    #[test]
+    fn draw_hline_page_clamps_to_last_column() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.draw_hline_page(0, 120, 200, 0x01);
+
+        let buffer = disp.buffer();
+
+        assert!(buffer[120..128].iter().all(|byte| *byte == 0x01));
+        assert_eq!(buffer.iter().filter(|byte| **byte != 0).count(), 8);
+    }
+
+    /// x coordinates of the pixels that are on in row `y`
+    fn row_pixels(disp: &SSD1306<MockInterface>, y: usize) -> Vec<usize> {
+        (0..128)
+            .filter(|x| disp.buffer[y / 8 * 128 + x] & (1 << (y % 8)) != 0)
+            .collect()
+    }
+
+    #[test]
+    fn line_partly_outside_clip() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.set_clip(Some(((10, 10), (20, 20))));
+        disp.draw_pixels((0..64).map(|x| (x, 15, true)));
+        // Entirely above the clip rectangle
+        disp.draw_pixels((0..64).map(|x| (x, 5, true)));
+
+        assert_eq!(row_pixels(&disp, 15), (10..30).collect::<Vec<_>>());
+        assert!(row_pixels(&disp, 5).is_empty());
+
+        disp.set_clip(None);
+        disp.draw_pixels((0..64).map(|x| (x, 5, true)));
+
+        assert_eq!(row_pixels(&disp, 5), (0..64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn regions_respect_clip() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        for byte in disp.buffer_mut() {
+            *byte = 0xFF;
+        }
+
+        disp.set_clip(Some(((4, 0), (8, 8))));
+        disp.clear_region((0, 0), (16, 16));
+
+        assert_eq!(
+            &disp.buffer[..16],
+            &[0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0, 0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF]
+        );
+        assert!(disp.buffer[16..].iter().all(|byte| *byte == 0xFF));
+
+        disp.invert_region((0, 0), (16, 16));
+
+        assert!(disp.buffer.iter().all(|byte| *byte == 0xFF));
+    }
+
+    #[test]
+    #[cfg(feature = "graphics-core")]
+    fn draw_target_clear_respects_clip() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.set_clip(Some(((0, 0), (8, 8))));
+        disp.clear(BinaryColor::On).unwrap();
+
+        assert!(disp.buffer[..8].iter().all(|byte| *byte == 0xFF));
+        assert!(disp.buffer[8..].iter().all(|byte| *byte == 0x00));
+    }
+
+    #[test]
+    #[cfg(feature = "graphics-core")]
+    fn draw_iter_line_partly_outside_clip() {
+        use embedded_graphics_core::geometry::Point;
+
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.set_clip(Some(((10, 10), (20, 20))));
+        disp.draw_iter((-5..40).map(|x| Pixel(Point::new(x, 12), BinaryColor::On)))
+            .unwrap();
+
+        assert_eq!(row_pixels(&disp, 12), (10..30).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn checkerboard_pattern_bytes() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x32);
+
+        disp.fill_pattern(Pattern::Checkerboard);
+
+        let buffer = disp.buffer();
+
+        let square = |page: usize, square: usize| &buffer[page * 128 + square * 8..][..8];
+
+        assert_eq!(square(0, 0), &[0xFF; 8]);
+        assert_eq!(square(0, 1), &[0x00; 8]);
+        assert_eq!(square(1, 0), &[0x00; 8]);
+        assert_eq!(square(1, 1), &[0xFF; 8]);
+        assert_eq!(square(3, 14), &[0x00; 8]);
+        assert_eq!(square(3, 15), &[0xFF; 8]);
+        assert_eq!(buffer.iter().filter(|byte| **byte == 0xFF).count(), 256);
+        assert_eq!(buffer.iter().filter(|byte| **byte == 0x00).count(), 256);
+
+        // Only the part of the buffer used by a 128x32 display is filled
+        assert_eq!(buffer.len(), 512);
+        assert!(disp.buffer[512..].iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    #[cfg(feature = "graphics-core")]
+    fn draw_target_clear_fills_used_buffer() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x32);
+
+        disp.flush().unwrap();
+        disp.clear(BinaryColor::On).unwrap();
+
+        assert!(disp.is_dirty());
+        assert!(disp.buffer[..512].iter().all(|byte| *byte == 0xFF));
+        assert!(disp.buffer[512..].iter().all(|byte| *byte == 0x00));
+
+        disp.clear(BinaryColor::Off).unwrap();
+
+        assert!(disp.buffer.iter().all(|byte| *byte == 0x00));
+    }
+
+    #[test]
+    #[cfg(feature = "diff-flush")]
+    fn diff_flush_sends_only_changes() {
+        let mut disp = SSD1306::new(MockInterface::new(), DisplaySize::Display128x64);
+
+        disp.flush().unwrap();
+        disp.set_pixel(10, 10, 1);
+        disp.iface.sent.clear();
+
+        assert_eq!(disp.flush().unwrap(), 1);
+        assert_eq!(disp.iface.sent.last(), Some(&Sent::Data(vec![1 << 2])));
+        assert_eq!(disp.iface.data_len(), 1);
+    }
 }
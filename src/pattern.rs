@@ -0,0 +1,48 @@
+//! Framebuffer test patterns
+
+/// Test pattern for `SSD1306::fill_pattern()`
+#[derive(Clone, Copy)]
+pub enum Pattern {
+    /// Checkerboard of 8 by 8 pixel squares
+    Checkerboard,
+    /// Lines along the display RAM rows, on every other row
+    HorizontalStripes,
+    /// Lines along the display RAM columns, on every other column
+    VerticalStripes,
+    /// Dithered gradient from off at column 0 to fully on at the last column
+    Gradient,
+}
+
+/// 4x4 ordered dithering thresholds
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl Pattern {
+    /// Get the framebuffer byte for `column` of `page` on a display `width` columns wide
+    pub(crate) fn byte(self, column: usize, page: usize, width: usize) -> u8 {
+        match self {
+            Pattern::Checkerboard => {
+                if (column / 8 + page) & 1 == 0 {
+                    0xFF
+                } else {
+                    0x00
+                }
+            }
+            Pattern::HorizontalStripes => 0x55,
+            Pattern::VerticalStripes => {
+                if column & 1 == 0 {
+                    0xFF
+                } else {
+                    0x00
+                }
+            }
+            Pattern::Gradient => {
+                // 17 levels, from no pixels to all 16 thresholds passed
+                let level = (column * 17 / width) as u8;
+
+                (0..8)
+                    .filter(|row| BAYER[row % 4][column % 4] < level)
+                    .fold(0, |byte, row| byte | (1 << row))
+            }
+        }
+    }
+}